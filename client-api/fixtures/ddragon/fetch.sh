@@ -0,0 +1,9 @@
+#!/bin/sh
+# Downloads unedited champion.json files of historic patches for the
+# `historic_champion_fixtures` test. Run from this directory and commit the files.
+set -eu
+
+for patch in 5.24.2 7.24.2 9.24.2 10.25.1 14.1.1; do
+    curl -fsSL -o "champion-$patch.json" \
+        "https://ddragon.leagueoflegends.com/cdn/$patch/data/en_US/champion.json"
+done
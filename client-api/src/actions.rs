@@ -1,16 +1,37 @@
+#[cfg(feature = "ddragon")]
+use crate::ddragon::{
+    index::ChampionIndex,
+    pool::{format_pools, generate_pools, PoolOptions},
+    types::Champion,
+    DDragon,
+};
+#[cfg(feature = "scoreboard")]
+use crate::scoreboard::Scoreboard;
+#[cfg(feature = "stats")]
+use crate::stats::rating::{self, KeyBy, PlayerId, RatingSource};
 use crate::{
     client::Client,
+    endpoints::match_history::MatchHistoryFilter,
+    export::{HttpSink, HttpSinkConfig, MatchSink, SyncState, SyncSummary},
     types::{
-        LolChampSelectChampSelectAction, LolChatConversationMessageResource, LolChatFriendResource,
+        LolChampSelectChampSelectAction, LolChampSelectChampSelectMySelection,
+        LolChatConversationMessageResource, LolChatFriendResource, LolGameflowGameflowPhase,
         LolLobbyLobbyChangeGameDto, LolLobbyLobbyCustomGameConfiguration,
         LolLobbyLobbyCustomGameLobby, LolLobbyLobbyInvitationDto,
         LolLobbyQueueCustomGameSpectatorPolicy, LolLobbyQueueGameTypeConfig,
+        LolMatchHistoryMatchHistoryGame,
     },
     Error,
 };
-use futures::future::try_join_all;
+use async_std::task::sleep;
+use futures::TryStreamExt;
 use itertools::Itertools;
 use rand::prelude::*;
+use std::{
+    collections::{BTreeSet, HashSet},
+    pin::pin,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 enum Queues {
     Arena,
@@ -46,6 +67,39 @@ pub enum Map {
 /// # Errors
 /// Fails if the custom game chat cannot be found or the client api cannot be reached.
 pub async fn randomize_teams(client: &Client) -> Result<(), Error> {
+    shuffle_and_announce_teams(client, |teams| Ok(format_teams(teams.iter()))).await
+}
+
+/// Like [`randomize_teams`], but also draws random champion options for every player
+/// and posts them with the teams. Champion names are in the locale of `ddragon`.
+///
+/// # Errors
+/// Fails if the custom game chat cannot be found, if not enough champions match the
+/// filters, or if the client api or Data Dragon cannot be reached.
+#[cfg(feature = "ddragon")]
+pub async fn randomize_teams_with_champions(
+    client: &Client,
+    ddragon: &DDragon,
+    options: &PoolOptions,
+) -> Result<(), Error> {
+    let champions = latest_champions(ddragon).await?;
+    shuffle_and_announce_teams(client, |teams| {
+        let teams: Vec<Vec<_>> = teams
+            .iter()
+            .map(|team| team.iter().map(|&name| name.to_owned()).collect())
+            .collect();
+        let pools = generate_pools(champions.data.values(), &teams, options, &mut thread_rng())?;
+        Ok(format_pools(&pools))
+    })
+    .await
+}
+
+/// Shuffles the players in the current lobby into teams, posts the message built by
+/// `announce` from their summoner names and moves the players if the lobby is arena.
+async fn shuffle_and_announce_teams(
+    client: &Client,
+    announce: impl FnOnce(&[&[&str]]) -> Result<String, Error>,
+) -> Result<(), Error> {
     // Create teams
     let lobby = client.get_lol_lobby_v2_lobby().await?;
 
@@ -64,20 +118,67 @@ pub async fn randomize_teams(client: &Client) -> Result<(), Error> {
 
     let teams = players.chunks(team_size).collect_vec();
 
+    let names = players
+        .iter()
+        .map(|player| player.summoner_name.as_str())
+        .collect_vec();
+    let message = announce(&names.chunks(team_size).collect_vec())?;
+    post_to_custom_game_chat(client, message).await?;
+
+    // Move players if gamemode is arena
+    if matches!(gamemode, Queues::Arena | Queues::Arena16) {
+        arena::move_team_members(client, &lobby.local_member, &teams).await?;
+    }
+
+    Ok(())
+}
+
+/// Gets all players in the current lobby, splits them into the two teams with the most
+/// even total rating and posts them in the lobby chat. Players are looked up in
+/// `ratings` by `key_by`.
+///
+/// # Errors
+/// Fails if the lobby has too many players to balance, if the custom game chat cannot
+/// be found or if the client api cannot be reached.
+#[cfg(feature = "stats")]
+pub async fn balance_teams(
+    client: &Client,
+    ratings: &impl RatingSource,
+    key_by: KeyBy,
+) -> Result<(), Error> {
+    let lobby = client.get_lol_lobby_v2_lobby().await?;
+    let (first, second) = rating::balance_teams(
+        &lobby.members,
+        |member| match key_by {
+            KeyBy::SummonerId => PlayerId::SummonerId(member.summoner_id),
+            KeyBy::Puuid => PlayerId::Puuid(member.puuid.clone()),
+        },
+        ratings,
+    )?;
+
+    let teams = [first, second].map(|team| {
+        team.iter()
+            .map(|member| member.summoner_name.as_str())
+            .collect_vec()
+    });
+    post_to_custom_game_chat(client, format_teams(teams.iter())).await
+}
+
+/// Formats teams of summoner names for the lobby chat.
+fn format_teams<'a, T: AsRef<[&'a str]>>(teams: impl Iterator<Item = T>) -> String {
     #[allow(unstable_name_collisions)]
-    let teams_output: String = std::iter::once(".\n".to_owned())
+    std::iter::once(".\n".to_owned())
         .chain(
-            players
-                .iter()
-                .map(|player| player.summoner_name.as_ref())
-                .collect_vec()
-                .chunks(team_size)
+            teams
                 .enumerate()
-                .map(|(i, team)| format!("Team {}:\n{}", i + 1, team.join("\n")))
+                .map(|(i, team)| format!("Team {}:\n{}", i + 1, team.as_ref().join("\n")))
                 .intersperse("\n----------\n".into()),
         )
-        .collect();
+        .collect()
+}
 
+/// Posts `body` in the custom game lobby chat.
+async fn post_to_custom_game_chat(client: &Client, body: String) -> Result<(), Error> {
     // Find custom game chat
     let conversations = client.get_lol_chat_v1_conversations().await?;
 
@@ -86,22 +187,76 @@ pub async fn randomize_teams(client: &Client) -> Result<(), Error> {
         .find(|x| x.type_ == "customGame")
         .ok_or(Error::LobbyNotFound)?;
 
-    // Post teams in chat
     let post_body = LolChatConversationMessageResource {
-        body: teams_output,
+        body,
         type_: "groupchat".to_string(),
         ..Default::default()
     };
     client
         .post_lol_chat_v1_conversations_by_id_messages(&custom_game_chat.id, post_body)
         .await?;
+    Ok(())
+}
 
-    // Move players if gamemode is arena
-    if matches!(gamemode, Queues::Arena | Queues::Arena16) {
-        arena::move_team_members(client, &lobby.local_member, &teams).await?;
+/// Draws random champion pools for the teams in the current custom game lobby and posts
+/// them in the lobby chat. Champion names are in the locale of `ddragon`.
+///
+/// # Errors
+/// Fails if the player is not in a custom game lobby, if not enough champions match the
+/// filters, or if the client api or Data Dragon cannot be reached.
+#[cfg(feature = "ddragon")]
+pub async fn post_champion_pools(
+    client: &Client,
+    ddragon: &DDragon,
+    options: &PoolOptions,
+) -> Result<(), Error> {
+    let lobby = client.get_lol_lobby_v2_lobby().await?;
+    let teams: Vec<Vec<_>> = [
+        &lobby.game_config.custom_team100,
+        &lobby.game_config.custom_team200,
+    ]
+    .into_iter()
+    .filter(|team| !team.is_empty())
+    .map(|team| team.iter().map(|x| x.summoner_name.clone()).collect())
+    .collect();
+    if teams.is_empty() {
+        return Err(Error::LobbyNotFound);
     }
 
-    Ok(())
+    let champions = latest_champions(ddragon).await?;
+    let pools = generate_pools(champions.data.values(), &teams, options, &mut thread_rng())?;
+
+    post_to_custom_game_chat(client, format_pools(&pools)).await
+}
+
+/// Champions of the newest patch, in the locale of `ddragon`.
+#[cfg(feature = "ddragon")]
+async fn latest_champions(ddragon: &DDragon) -> Result<Champion, Error> {
+    let patch = ddragon
+        .versions()
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::Custom("Data Dragon has no versions".into()))?;
+    ddragon.champion(&patch).await
+}
+
+/// Posts a compact scoreboard of the most recent custom game in the lobby chat.
+/// Champion names are in the locale of `ddragon`.
+///
+/// # Errors
+/// Fails if there is no recent custom game, if the custom game chat cannot be found, or
+/// if the client api or Data Dragon cannot be reached.
+#[cfg(feature = "scoreboard")]
+pub async fn post_last_game_scoreboard(client: &Client, ddragon: &DDragon) -> Result<(), Error> {
+    let game = pin!(client.match_history(MatchHistoryFilter::custom_games().limit(10)))
+        .try_next()
+        .await?
+        .ok_or(Error::NoGamesInMatchHistory)?;
+    let patch = ddragon.patch_for_game_version(&game.game_version).await?;
+    let champions = ChampionIndex::from(ddragon.champion(&patch).await?);
+
+    post_to_custom_game_chat(client, Scoreboard::new(&game, &champions, None).compact()).await
 }
 
 /// Creates a custom game with tournament draft on Summoner's Rift.
@@ -192,61 +347,283 @@ pub async fn post_custom_games_to_pasanapi(
     client: &Client,
     num_matches_to_check: u8,
 ) -> Result<(), Error> {
-    let req_client = reqwest::Client::new();
+    let mut sink = HttpSink::new(HttpSinkConfig::default())?;
+    export_custom_games(client, num_matches_to_check, &mut [&mut sink]).await
+}
 
-    let match_history = client
-        .get_lol_match_history_v1_products_lol_current_summoner_matches(
-            None,
-            Some(num_matches_to_check),
-        )
-        .await?
-        .games
-        .games;
+/// Fetches the given number of games from the player's match history and sends any
+/// custom games with 10 participants to every sink.
+///
+/// # Errors
+/// Fails if the client api cannot be reached.
+pub async fn export_custom_games(
+    client: &Client,
+    num_matches_to_check: u8,
+    sinks: &mut [&mut dyn MatchSink],
+) -> Result<(), Error> {
+    let games: Vec<_> = client
+        .match_history(MatchHistoryFilter::custom_games().limit(num_matches_to_check.into()))
+        .try_collect()
+        .await?;
 
-    let match_history = match_history
-        .into_iter()
-        .filter(|x| x.map_id == 11 && x.game_type == "CUSTOM_GAME" && x.game_mode == "CLASSIC")
-        .map(|x| client.get_lol_match_history_v1_games_by_game_id(x.game_id));
+    for sink in sinks {
+        let num_inserted = sink
+            .send(&games)
+            .await
+            .into_iter()
+            .filter(Result::is_ok)
+            .count();
 
-    let post_responses = try_join_all(match_history)
-        .await?
-        .into_iter()
-        .filter(|x| x.participants.len() == 10)
-        .map(|x| {
-            req_client
-                .post("https://api.påsan.com/match")
-                .json(&x)
-                .send()
-        });
+        log::info!(
+            "Successfully sent {num_inserted} custom games to {}",
+            sink.name()
+        );
+    }
 
-    let responses = try_join_all(post_responses).await?;
+    Ok(())
+}
 
-    let num_inserted = responses
-        .into_iter()
-        .filter(|x| x.status().is_success())
-        .count();
+/// Sends `games` to every sink that has not accepted them yet and records the outcome
+/// in `state`. Sinks are identified by their position in `sinks`, so the same sinks
+/// should be passed in the same order on every sync.
+async fn send_to_sinks(
+    state: &mut SyncState,
+    sinks: &mut [&mut dyn MatchSink],
+    games: &[LolMatchHistoryMatchHistoryGame],
+    summary: &mut SyncSummary,
+) {
+    let mut rejected: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); games.len()];
+    for (sink_index, sink) in sinks.iter_mut().enumerate() {
+        let pending: Vec<_> = (0..games.len())
+            .filter(|&i| {
+                state
+                    .failed
+                    .get(&games[i].game_id)
+                    .is_none_or(|sinks| sinks.contains(&sink_index))
+            })
+            .collect();
+        if pending.is_empty() {
+            continue;
+        }
+        let batch: Vec<_> = pending.iter().map(|&i| games[i].clone()).collect();
+        for (&i, result) in pending.iter().zip(sink.send(&batch).await) {
+            if let Err(e) = result {
+                log::warn!("{} rejected game {}: {e}", sink.name(), games[i].game_id);
+                rejected[i].insert(sink_index);
+            }
+        }
+    }
+    for (game, rejected) in games.iter().zip(rejected) {
+        if rejected.is_empty() {
+            state.failed.remove(&game.game_id);
+            state.exported.insert(game.game_id);
+            summary.new += 1;
+        } else {
+            state.failed.insert(game.game_id, rejected);
+            summary.failed += 1;
+        }
+    }
+}
+
+/// Sends custom games with 10 participants that are not yet in `state` to every sink,
+/// checking at most `max_games` custom games back in the match history.
+///
+/// Only games newer than the previous sync are fetched, plus any games that failed
+/// before. A game counts as exported once every sink has accepted it, and a failed game
+/// is only sent again to the sinks that rejected it. When `max_games` stops the walk
+/// before it reaches the previous sync, the next sync continues below the oldest game
+/// walked. `state` is updated as games are exported, so it should be saved even if the
+/// sync fails.
+///
+/// # Errors
+/// Fails if the match history cannot be fetched.
+pub async fn sync_custom_games(
+    client: &Client,
+    state: &mut SyncState,
+    sinks: &mut [&mut dyn MatchSink],
+    max_games: u32,
+) -> Result<SyncSummary, Error> {
+    let mut summary = SyncSummary::default();
+    let mut seen = HashSet::new();
+    let mut candidates: Vec<_> = state.failed.keys().copied().collect();
+    let mut newest_game_creation = state
+        .newest_game_creation
+        .max(state.pending_newest_game_creation);
+    let mut oldest_game_creation = u64::MAX;
+    let mut walked = 0;
+    let mut complete = false;
+
+    // Exported games are skipped without fetching them in full
+    let mut filter = MatchHistoryFilter {
+        min_participants: None,
+        ..MatchHistoryFilter::custom_games()
+            .after(UNIX_EPOCH + Duration::from_millis(state.newest_game_creation + 1))
+    };
+    if let Some(resume_before) = state.resume_before {
+        filter = filter.before(UNIX_EPOCH + Duration::from_millis(resume_before));
+    }
+    let mut match_history = pin!(client.match_history(filter));
+    while walked < max_games {
+        let Some(game) = match_history.try_next().await? else {
+            complete = true;
+            break;
+        };
+        walked += 1;
+        newest_game_creation = newest_game_creation.max(game.game_creation);
+        oldest_game_creation = oldest_game_creation.min(game.game_creation);
+        if !seen.insert(game.game_id) {
+            continue;
+        }
+        if state.exported.contains(&game.game_id) {
+            summary.skipped += 1;
+        } else if !state.failed.contains_key(&game.game_id) {
+            candidates.push(game.game_id);
+        }
+    }
 
-    log::info!("Successfully sent {num_inserted} custom games");
+    let mut games = Vec::with_capacity(candidates.len());
+    for game_id in candidates {
+        match client
+            .get_lol_match_history_v1_games_by_game_id(game_id)
+            .await
+        {
+            Ok(game) if game.participants.len() == 10 => games.push(game),
+            Ok(_) => {
+                state.failed.remove(&game_id);
+            }
+            Err(e) => {
+                log::warn!("Failed to fetch game {game_id}: {e}");
+                state
+                    .failed
+                    .entry(game_id)
+                    .or_insert_with(|| (0..sinks.len()).collect());
+                summary.failed += 1;
+            }
+        }
+    }
+    send_to_sinks(state, sinks, &games, &mut summary).await;
 
+    // Games older than the limit have not been walked yet, so the watermark only moves
+    // once the walk gets through to the previous sync or the end of the match history
+    if complete {
+        state.newest_game_creation = newest_game_creation;
+        state.resume_before = None;
+        state.pending_newest_game_creation = 0;
+    } else {
+        state.resume_before = Some(oldest_game_creation);
+        state.pending_newest_game_creation = newest_game_creation;
+    }
+
+    log::info!(
+        "Synced custom games: {} new, {} skipped, {} failed",
+        summary.new,
+        summary.skipped,
+        summary.failed
+    );
+    Ok(summary)
+}
+
+/// Polls the gameflow phase every `interval` until it is `phase`.
+///
+/// # Errors
+/// Fails if the client api cannot be reached.
+pub async fn wait_for_gameflow_phase(
+    client: &Client,
+    phase: LolGameflowGameflowPhase,
+    interval: Duration,
+) -> Result<(), Error> {
+    while client.get_lol_gameflow_v1_gameflow_phase().await? != phase {
+        sleep(interval).await;
+    }
     Ok(())
 }
 
+/// Waits for the gameflow to reach `EndOfGame` and returns the end-of-game stats in the
+/// same shape as a match history game, without waiting for match history to show it.
+///
+/// The map is read from the gameflow session. Neither has the creation time of the
+/// game, so `game_creation` is taken as the capture time minus the game length, which
+/// is close to when the game started.
+///
+/// # Errors
+/// Fails if the client api cannot be reached.
+pub async fn capture_end_of_game(
+    client: &Client,
+    interval: Duration,
+) -> Result<LolMatchHistoryMatchHistoryGame, Error> {
+    wait_for_gameflow_phase(client, LolGameflowGameflowPhase::EndOfGame, interval).await?;
+    let mut game: LolMatchHistoryMatchHistoryGame = client
+        .get_lol_end_of_game_v1_eog_stats_block()
+        .await?
+        .into();
+    let captured_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .saturating_sub(Duration::from_secs(game.game_duration.into()));
+    game.game_creation = u64::try_from(captured_at.as_millis()).unwrap_or(u64::MAX);
+
+    let session = client.get_lol_gameflow_v1_session().await?;
+    if session.game_data.game_id == game.game_id {
+        game.map_id = u32::try_from(session.map.id).unwrap_or_default();
+    } else {
+        log::warn!(
+            "Gameflow session is for game {}, not {}, leaving the map unset",
+            session.game_data.game_id,
+            game.game_id
+        );
+    }
+    Ok(game)
+}
+
+/// Captures the next game to end with [`capture_end_of_game`] and sends it to every
+/// sink if it is a custom game with 10 participants that is not yet in `state`. Returns
+/// whether it is exported.
+///
+/// The game is recorded in `state` like in [`sync_custom_games`], so a later sync does
+/// not send it again. Call it again once the gameflow has left `EndOfGame`, or the same
+/// game is captured twice.
+///
+/// # Errors
+/// Fails if the client api cannot be reached.
+pub async fn export_custom_game_at_end_of_game(
+    client: &Client,
+    state: &mut SyncState,
+    sinks: &mut [&mut dyn MatchSink],
+    interval: Duration,
+) -> Result<bool, Error> {
+    let game = capture_end_of_game(client, interval).await?;
+    let filter = MatchHistoryFilter::custom_games();
+    let too_few_participants = filter
+        .min_participants
+        .is_some_and(|min| game.participants.len() < min);
+    if !filter.matches(&game) || too_few_participants {
+        log::info!(
+            "Game {} is not a custom game, skipping export",
+            game.game_id
+        );
+        return Ok(false);
+    }
+    if state.exported.contains(&game.game_id) {
+        log::info!("Game {} is already exported", game.game_id);
+        return Ok(false);
+    }
+
+    let game_id = game.game_id;
+    send_to_sinks(state, sinks, &[game], &mut SyncSummary::default()).await;
+    Ok(state.exported.contains(&game_id))
+}
+
 /// Checks players previous 10 games played and invites everyone from the first custom
 /// game found.
 ///
 /// # Errors
 /// Fails if client api cannot be reached, or if no recent custom games can be found.
 pub async fn invite_from_previous(client: &Client) -> Result<(), Error> {
-    let match_history = client
-        .get_lol_match_history_v1_products_lol_current_summoner_matches(None, Some(10))
-        .await?
-        .games
-        .games;
-
-    let last_game = match_history
-        .into_iter()
-        .find(|x| x.game_type == "CUSTOM_GAME")
-        .ok_or(Error::NoGamesInMatchHistory)?;
+    let last_game =
+        pin!(client.match_history(MatchHistoryFilter::new().game_type("CUSTOM_GAME").limit(10)))
+            .try_next()
+            .await?
+            .ok_or(Error::NoGamesInMatchHistory)?;
 
     let summoners: Vec<_> = client
         .get_lol_match_history_v1_games_by_game_id(last_game.game_id)
@@ -300,6 +677,39 @@ pub async fn select_champion(client: &Client, champion_id: i32) -> Result<(), Er
     Ok(())
 }
 
+/// Selects the skin with the given skin id for the currently selected champion. Skin ids
+/// can be found in the Data Dragon champion details.
+///
+/// # Errors
+/// Fails if the client api cannot be reached, or if the player is not currently in champion
+/// select
+pub async fn select_skin(client: &Client, skin_id: i32) -> Result<(), Error> {
+    client
+        .patch_lol_champ_select_v1_session_my_selection(LolChampSelectChampSelectMySelection {
+            selected_skin_id: Some(skin_id),
+            ..Default::default()
+        })
+        .await
+}
+
+/// Selects the champion best matching `name`, which can be a champion id, alias or
+/// (approximate) display name.
+///
+/// # Errors
+/// Fails if no champion matches `name`, or for the same reasons as [`select_champion`]
+#[cfg(feature = "ddragon")]
+pub async fn select_champion_by_name(
+    client: &Client,
+    index: &ChampionIndex,
+    name: &str,
+) -> Result<(), Error> {
+    let champion_id = index
+        .find(name)
+        .and_then(|champion| champion.key.parse().ok())
+        .ok_or_else(|| Error::ChampionNotFound(name.to_owned()))?;
+    select_champion(client, champion_id).await
+}
+
 mod arena {
     use std::time::Duration;
 
@@ -414,6 +824,7 @@ mod arena {
         teams: &[&[&LolLobbyLobbyParticipantDto]],
     ) -> Result<(), Error> {
         let mut arena_teams = ArenaTeam::from_player_list(client, local_member, teams);
+        #[allow(clippy::while_let_loop)]
         loop {
             let Some(next_pos) = arena_teams
                 .pos_of_player_with_target_at_local_player()
@@ -428,3 +839,391 @@ mod arena {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{export_custom_game_at_end_of_game, sync_custom_games};
+    use crate::{
+        client::Client,
+        export::{HttpSink, HttpSinkConfig, JsonLinesSink, SyncState, SyncSummary},
+        test_utils::{serve, serve_recording, temp_dir},
+        types::{
+            LolEndOfGameEndOfGamePlayer, LolEndOfGameEndOfGameStats, LolEndOfGameEndOfGameTeam,
+            LolGameflowGameflowGameData, LolGameflowGameflowGameMap, LolGameflowGameflowPhase,
+            LolGameflowGameflowSession, LolMatchHistoryMatchHistoryGame,
+            LolMatchHistoryMatchHistoryList, LolMatchHistoryMatchHistoryParticipant,
+        },
+        Error,
+    };
+    use std::{
+        fs,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+
+    const MATCHES: &str = "/lol-match-history/v1/products/lol/current-summoner/matches";
+
+    fn summary(game_id: u64, game_type: &str) -> LolMatchHistoryMatchHistoryGame {
+        LolMatchHistoryMatchHistoryGame {
+            game_id,
+            game_creation: game_id * 100,
+            map_id: 11,
+            game_mode: "CLASSIC".to_owned(),
+            game_type: game_type.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    fn match_history(games: Vec<LolMatchHistoryMatchHistoryGame>) -> String {
+        let mut list = LolMatchHistoryMatchHistoryList::default();
+        list.games.games = games;
+        serde_json::to_string(&list).unwrap()
+    }
+
+    fn full_game(game_id: u64) -> String {
+        let mut game = summary(game_id, "CUSTOM_GAME");
+        game.participants = vec![LolMatchHistoryMatchHistoryParticipant::default(); 10];
+        serde_json::to_string(&game).unwrap()
+    }
+
+    fn stand_in() -> Result<Client, Error> {
+        Client::from_base_url(&serve(vec![
+            (
+                "/lol-match-history/v1/products/lol/current-summoner/matches?begIndex=0&endIndex=50",
+                match_history(vec![
+                    summary(3, "CUSTOM_GAME"),
+                    summary(2, "MATCHED_GAME"),
+                    summary(1, "CUSTOM_GAME"),
+                ]),
+            ),
+            (MATCHES, match_history(vec![])),
+            ("/lol-match-history/v1/games/1", full_game(1)),
+            ("/lol-match-history/v1/games/3", full_game(3)),
+        ]))
+    }
+
+    #[tokio::test]
+    async fn sync_only_sends_new_games() -> Result<(), Error> {
+        let client = stand_in()?;
+        let dir = temp_dir("sync-new");
+        fs::create_dir_all(&dir)?;
+        let mut sink = JsonLinesSink::new(dir.join("games.jsonl"))?;
+        let mut state = SyncState::default();
+
+        let summary = sync_custom_games(&client, &mut state, &mut [&mut sink], 500).await?;
+        assert_eq!(
+            summary,
+            SyncSummary {
+                new: 2,
+                skipped: 0,
+                failed: 0
+            }
+        );
+        assert_eq!(state.exported, [1, 3].into());
+        assert_eq!(state.newest_game_creation, 300);
+
+        let summary = sync_custom_games(&client, &mut state, &mut [&mut sink], 500).await?;
+        assert_eq!(summary, SyncSummary::default());
+
+        // Forgetting how far the last sync got skips exported games
+        state.newest_game_creation = 0;
+        let summary = sync_custom_games(&client, &mut state, &mut [&mut sink], 500).await?;
+        assert_eq!(summary.skipped, 2);
+        assert_eq!(
+            fs::read_to_string(dir.join("games.jsonl"))?.lines().count(),
+            2
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sync_retries_failed_games() -> Result<(), Error> {
+        let client = stand_in()?;
+        let dir = temp_dir("sync-retry");
+        fs::create_dir_all(&dir)?;
+        let mut sink = JsonLinesSink::new(dir.join("games.jsonl"))?;
+        let mut rejecting = HttpSink::new(HttpSinkConfig::with_base_url(&serve(vec![]))?)?;
+        let mut state = SyncState::default();
+
+        let summary =
+            sync_custom_games(&client, &mut state, &mut [&mut sink, &mut rejecting], 500).await?;
+        assert_eq!(summary.failed, 2);
+        assert_eq!(state.failed, [(1, [1].into()), (3, [1].into())].into());
+
+        let (base_url, requests) = serve_recording(vec![("/match", String::new())]);
+        let mut accepting = HttpSink::new(HttpSinkConfig::with_base_url(&base_url)?)?;
+        let summary =
+            sync_custom_games(&client, &mut state, &mut [&mut sink, &mut accepting], 500).await?;
+        assert_eq!(summary.new, 2);
+        assert!(state.failed.is_empty());
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        // The json lines sink accepted both games the first time
+        assert_eq!(
+            fs::read_to_string(dir.join("games.jsonl"))?.lines().count(),
+            2
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sync_resumes_after_game_limit() -> Result<(), Error> {
+        let client = stand_in()?;
+        let dir = temp_dir("sync-resume");
+        fs::create_dir_all(&dir)?;
+        let mut sink = JsonLinesSink::new(dir.join("games.jsonl"))?;
+        let mut state = SyncState::default();
+
+        let summary = sync_custom_games(&client, &mut state, &mut [&mut sink], 1).await?;
+        assert_eq!(summary.new, 1);
+        assert_eq!(state.exported, [3].into());
+        assert_eq!(state.newest_game_creation, 0);
+        assert_eq!(state.resume_before, Some(300));
+
+        let summary = sync_custom_games(&client, &mut state, &mut [&mut sink], 1).await?;
+        assert_eq!(summary.new, 1);
+        assert_eq!(state.exported, [1, 3].into());
+        assert_eq!(state.resume_before, Some(100));
+
+        let summary = sync_custom_games(&client, &mut state, &mut [&mut sink], 1).await?;
+        assert_eq!(summary, SyncSummary::default());
+        assert_eq!(state.newest_game_creation, 300);
+        assert_eq!(state.resume_before, None);
+        Ok(())
+    }
+
+    #[cfg(feature = "ddragon")]
+    #[tokio::test]
+    async fn announces_random_teams_with_local_champion_names() -> Result<(), Error> {
+        use super::randomize_teams_with_champions;
+        use crate::{
+            ddragon::{
+                pool::PoolOptions,
+                types::{Champion, ChampionData},
+                DDragon,
+            },
+            types::{
+                LolChatConversationMessageResource, LolChatConversationResource, LolLobbyLobbyDto,
+                LolLobbyLobbyParticipantDto,
+            },
+        };
+
+        let champions = |name: &str| {
+            serde_json::to_string(&Champion {
+                data: [("Wukong", "62", name), ("Ahri", "103", "Ahri")]
+                    .into_iter()
+                    .map(|(id, key, name)| {
+                        let champion = ChampionData {
+                            id: id.to_owned(),
+                            key: key.to_owned(),
+                            name: name.to_owned(),
+                            ..Default::default()
+                        };
+                        (id.to_owned(), champion)
+                    })
+                    .collect(),
+                ..Default::default()
+            })
+            .unwrap()
+        };
+        let ddragon_url = serve(vec![
+            ("/api/versions.json", r#"["14.1.1"]"#.to_owned()),
+            ("/cdn/14.1.1/data/en_US/champion.json", champions("Wukong")),
+            (
+                "/cdn/14.1.1/data/nb_NO/champion.json",
+                champions("Apekongen"),
+            ),
+        ]);
+        let lobby = LolLobbyLobbyDto {
+            members: ["Player 1", "Player 2"]
+                .map(|name| LolLobbyLobbyParticipantDto {
+                    summoner_name: name.to_owned(),
+                    ..Default::default()
+                })
+                .into(),
+            ..Default::default()
+        };
+        let conversation = LolChatConversationResource {
+            id: "lobby".to_owned(),
+            name: String::new(),
+            pid: String::new(),
+            game_name: String::new(),
+            game_tag: String::new(),
+            type_: "customGame".to_owned(),
+            inviter_id: String::new(),
+            password: String::new(),
+            target_region: String::new(),
+            is_muted: false,
+            unread_message_count: 0,
+            last_message: None,
+        };
+        let (base_url, requests) = serve_recording(vec![
+            ("/lol-lobby/v2/lobby", serde_json::to_string(&lobby)?),
+            (
+                "/lol-chat/v1/conversations",
+                serde_json::to_string(&[conversation])?,
+            ),
+            (
+                "/lol-chat/v1/conversations/lobby/messages",
+                serde_json::to_string(&LolChatConversationMessageResource::default())?,
+            ),
+        ]);
+        let client = Client::from_base_url(&base_url)?;
+        let ddragon = DDragon::new()
+            .with_base_url(&ddragon_url)?
+            .with_locale("nb_NO");
+        let options = PoolOptions {
+            options_per_player: 1,
+            ..Default::default()
+        };
+
+        randomize_teams_with_champions(&client, &ddragon, &options).await?;
+
+        let requests = requests.lock().unwrap();
+        let message: LolChatConversationMessageResource =
+            serde_json::from_str(&requests.last().unwrap().body)?;
+        assert!(message.body.contains("Apekongen"));
+        assert!(!message.body.contains("Wukong"));
+        assert!(message.body.contains("Team 2:"));
+        Ok(())
+    }
+
+    fn end_of_game(game_type: &str) -> String {
+        let team = |team_id, is_winning_team| LolEndOfGameEndOfGameTeam {
+            team_id,
+            is_winning_team,
+            players: (0..5)
+                .map(|i| LolEndOfGameEndOfGamePlayer {
+                    champion_id: i + 1,
+                    summoner_name: format!("Player {i}"),
+                    team_id,
+                    items: vec![1001, 3006],
+                    stats: [("CHAMPIONS_KILLED".to_owned(), i64::from(i))].into(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        serde_json::to_string(&LolEndOfGameEndOfGameStats {
+            game_id: 42,
+            game_length: 1800,
+            game_mode: "CLASSIC".to_owned(),
+            game_type: game_type.to_owned(),
+            teams: vec![team(100, true), team(200, false)],
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn gameflow_session(game_id: u64, map_id: i32) -> String {
+        serde_json::to_string(&LolGameflowGameflowSession {
+            phase: LolGameflowGameflowPhase::EndOfGame,
+            game_data: LolGameflowGameflowGameData {
+                game_id,
+                ..Default::default()
+            },
+            map: LolGameflowGameflowGameMap {
+                id: map_id,
+                ..Default::default()
+            },
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn exports_end_of_game_stats() -> Result<(), Error> {
+        let client = Client::from_base_url(&serve(vec![
+            (
+                "/lol-gameflow/v1/gameflow-phase",
+                "\"EndOfGame\"".to_owned(),
+            ),
+            (
+                "/lol-end-of-game/v1/eog-stats-block",
+                end_of_game("CUSTOM_GAME"),
+            ),
+            ("/lol-gameflow/v1/session", gameflow_session(42, 11)),
+        ]))?;
+        let dir = temp_dir("end-of-game");
+        fs::create_dir_all(&dir)?;
+        let mut sink = JsonLinesSink::new(dir.join("games.jsonl"))?;
+
+        let mut state = SyncState::default();
+
+        let sent = export_custom_game_at_end_of_game(
+            &client,
+            &mut state,
+            &mut [&mut sink],
+            Duration::ZERO,
+        )
+        .await?;
+        assert!(sent);
+        assert_eq!(state.exported, [42].into());
+
+        // A game that is already exported is not sent again
+        let sent = export_custom_game_at_end_of_game(
+            &client,
+            &mut state,
+            &mut [&mut sink],
+            Duration::ZERO,
+        )
+        .await?;
+        assert!(!sent);
+
+        let game: LolMatchHistoryMatchHistoryGame =
+            serde_json::from_str(&fs::read_to_string(dir.join("games.jsonl"))?)?;
+        assert_eq!(game.game_id, 42);
+        assert_eq!(game.map_id, 11);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let started = now - Duration::from_secs(1800);
+        assert!(
+            game.game_creation
+                .abs_diff(started.as_millis().try_into().unwrap())
+                < 60_000
+        );
+        assert_eq!(game.participants.len(), 10);
+        assert_eq!(
+            game.participant_identities[6].player.summoner_name,
+            "Player 1"
+        );
+        let participant = &game.participants[6];
+        assert_eq!(participant.participant_id, 7);
+        assert_eq!(participant.team_id, 200);
+        assert_eq!(participant.stats.kills, 1);
+        assert_eq!(participant.stats.item1, 3006);
+        assert!(!participant.stats.win);
+        assert_eq!(game.teams[0].win, "Win");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn skips_end_of_game_stats_of_other_games() -> Result<(), Error> {
+        let client = Client::from_base_url(&serve(vec![
+            (
+                "/lol-gameflow/v1/gameflow-phase",
+                "\"EndOfGame\"".to_owned(),
+            ),
+            (
+                "/lol-end-of-game/v1/eog-stats-block",
+                end_of_game("MATCHED_GAME"),
+            ),
+            ("/lol-gameflow/v1/session", gameflow_session(42, 11)),
+        ]))?;
+        let dir = temp_dir("end-of-game-matched");
+        fs::create_dir_all(&dir)?;
+        let mut sink = JsonLinesSink::new(dir.join("games.jsonl"))?;
+
+        let mut state = SyncState::default();
+
+        let sent = export_custom_game_at_end_of_game(
+            &client,
+            &mut state,
+            &mut [&mut sink],
+            Duration::ZERO,
+        )
+        .await?;
+        assert!(!sent);
+        assert!(state.exported.is_empty());
+        assert!(fs::read_to_string(dir.join("games.jsonl"))
+            .unwrap_or_default()
+            .is_empty());
+        Ok(())
+    }
+}
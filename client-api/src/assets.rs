@@ -0,0 +1,259 @@
+use crate::{
+    client::{parse_base_url, Client},
+    Error,
+};
+use reqwest::Url;
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Component, Path, PathBuf},
+    sync::Mutex,
+};
+
+const DEFAULT_CDRAGON_BASE_URL: &str = "https://raw.communitydragon.org/latest/";
+
+/// An image shipped with the client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Asset {
+    /// Square champion portrait by champion id.
+    ChampionSquare(i32),
+    /// Item icon by item id, e.g. `item0` in match history.
+    ItemIcon(u32),
+    /// Summoner profile icon by icon id.
+    ProfileIcon(i32),
+    /// Ranked emblem by tier, e.g. `gold`.
+    RankEmblem(String),
+    /// Any other client asset path, e.g. `/lol-game-data/assets/v1/perk-images/...`.
+    /// Paths with `..` or a drive prefix are rejected.
+    Path(String),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GameDataItem {
+    id: u32,
+    icon_path: String,
+}
+
+/// Downloads client assets, preferring the running client and falling back to
+/// CommunityDragon, and optionally caches them on disk.
+#[derive(Debug)]
+pub struct Assets {
+    client: Option<Client>,
+    http: reqwest::Client,
+    cdragon_base_url: Url,
+    cache_dir: Option<PathBuf>,
+    item_icons: Mutex<HashMap<u32, String>>,
+}
+
+impl Assets {
+    /// Creates an asset source. Without a `client` every asset is fetched from
+    /// CommunityDragon.
+    #[must_use]
+    pub fn new(client: Option<Client>) -> Self {
+        Self {
+            client,
+            http: reqwest::Client::new(),
+            cdragon_base_url: Url::parse(DEFAULT_CDRAGON_BASE_URL)
+                .expect("default base url is valid"),
+            cache_dir: None,
+            item_icons: Mutex::default(),
+        }
+    }
+
+    /// Uses `base_url` instead of the latest patch on CommunityDragon, e.g.
+    /// `https://raw.communitydragon.org/14.1`, whether or not it ends in a slash.
+    ///
+    /// # Errors
+    /// Fails if `base_url` is not a valid url
+    pub fn with_cdragon_base_url(mut self, base_url: &str) -> Result<Self, Error> {
+        self.cdragon_base_url = parse_base_url(base_url)?;
+        Ok(self)
+    }
+
+    /// Stores downloaded assets in `cache_dir`, mirroring the client asset paths.
+    #[must_use]
+    pub fn with_cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(cache_dir.into());
+        self
+    }
+
+    /// Raw image bytes of `asset`.
+    ///
+    /// # Errors
+    /// Fails if the asset path would leave the cache directory, or if the asset is
+    /// neither cached, available from the client nor from CommunityDragon
+    pub async fn get(&self, asset: &Asset) -> Result<Vec<u8>, Error> {
+        let path = self.client_path(asset).await?;
+        // Client paths start with a slash, but must stay inside the cache directory
+        let relative_path = Path::new(path.trim_start_matches('/'));
+        if !relative_path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(Error::InvalidAssetPath(path));
+        }
+        let cache_file = self.cache_dir.as_ref().map(|dir| dir.join(relative_path));
+        if let Some(file) = cache_file.as_ref().filter(|file| file.is_file()) {
+            return Ok(fs::read(file)?);
+        }
+
+        let bytes = self.download(&path).await?;
+        if let Some(file) = cache_file {
+            if let Some(parent) = file.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(file, &bytes)?;
+        }
+        Ok(bytes)
+    }
+
+    async fn client_path(&self, asset: &Asset) -> Result<String, Error> {
+        Ok(match asset {
+            Asset::ChampionSquare(id) => {
+                format!("/lol-game-data/assets/v1/champion-icons/{id}.png")
+            }
+            Asset::ProfileIcon(id) => format!("/lol-game-data/assets/v1/profile-icons/{id}.jpg"),
+            Asset::RankEmblem(tier) => format!(
+                "/lol-static-assets/images/ranked-emblem/emblem-{}.png",
+                tier.to_lowercase()
+            ),
+            Asset::Path(path) => path.clone(),
+            Asset::ItemIcon(id) => {
+                if let Some(path) = self.item_icons.lock().unwrap().get(id) {
+                    return Ok(path.clone());
+                }
+                let items: Vec<GameDataItem> = serde_json::from_slice(
+                    &self.download("/lol-game-data/assets/v1/items.json").await?,
+                )?;
+                let mut item_icons = self.item_icons.lock().unwrap();
+                item_icons.extend(items.into_iter().map(|item| (item.id, item.icon_path)));
+                item_icons
+                    .get(id)
+                    .cloned()
+                    .ok_or_else(|| Error::AssetNotFound(format!("item {id}")))?
+            }
+        })
+    }
+
+    async fn download(&self, path: &str) -> Result<Vec<u8>, Error> {
+        if let Some(client) = &self.client {
+            match client.get_bytes(path).await {
+                Ok(bytes) => return Ok(bytes),
+                Err(e) => {
+                    log::warn!("Failed to get {path} from client, trying CommunityDragon: {e}")
+                }
+            }
+        }
+        let url = self.cdragon_url(path)?;
+        log::info!("GET {url}");
+        Ok(self
+            .http
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?
+            .to_vec())
+    }
+
+    /// CommunityDragon mirrors the client plugins with lowercased paths.
+    fn cdragon_url(&self, path: &str) -> Result<Url, Error> {
+        let path = path.to_lowercase();
+        let plugin_path = if let Some(rest) = path.strip_prefix("/lol-game-data/assets/") {
+            format!("plugins/rcp-be-lol-game-data/global/default/{rest}")
+        } else if let Some(rest) = path.strip_prefix("/lol-static-assets/") {
+            format!("plugins/rcp-fe-lol-static-assets/global/default/{rest}")
+        } else {
+            return Err(Error::AssetNotFound(path));
+        };
+        Ok(self.cdragon_base_url.join(&plugin_path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Asset, Assets};
+    use crate::{
+        test_utils::{serve, temp_dir},
+        Error,
+    };
+
+    #[tokio::test]
+    async fn falls_back_to_cdragon_and_caches() -> Result<(), Error> {
+        let base_url = serve(vec![
+            (
+                "/plugins/rcp-be-lol-game-data/global/default/v1/champion-icons/4.png",
+                "twisted fate".to_owned(),
+            ),
+            (
+                "/plugins/rcp-be-lol-game-data/global/default/v1/items.json",
+                r#"[{"id":3031,"name":"Infinity Edge","iconPath":"/lol-game-data/assets/ASSETS/Items/Icons2D/3031_Marksman_T3_InfinityEdge.png"}]"#.to_owned(),
+            ),
+            (
+                "/plugins/rcp-be-lol-game-data/global/default/assets/items/icons2d/3031_marksman_t3_infinityedge.png",
+                "infinity edge".to_owned(),
+            ),
+        ]);
+        let cache_dir = temp_dir("assets");
+        let assets = Assets::new(None)
+            .with_cdragon_base_url(&base_url)?
+            .with_cache_dir(&cache_dir);
+
+        assert_eq!(
+            assets.get(&Asset::ChampionSquare(4)).await?,
+            b"twisted fate"
+        );
+        assert_eq!(assets.get(&Asset::ItemIcon(3031)).await?, b"infinity edge");
+        assert!(assets.get(&Asset::ProfileIcon(1)).await.is_err());
+
+        let offline = Assets::new(None)
+            .with_cdragon_base_url("http://127.0.0.1:9/")?
+            .with_cache_dir(&cache_dir);
+        assert_eq!(
+            offline.get(&Asset::ChampionSquare(4)).await?,
+            b"twisted fate"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cdragon_base_url_without_trailing_slash() -> Result<(), Error> {
+        let base_url = serve(vec![(
+            "/14.1/plugins/rcp-be-lol-game-data/global/default/v1/champion-icons/4.png",
+            "twisted fate".to_owned(),
+        )]);
+        let assets = Assets::new(None).with_cdragon_base_url(&format!("{base_url}14.1"))?;
+        assert_eq!(
+            assets.get(&Asset::ChampionSquare(4)).await?,
+            b"twisted fate"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rejects_paths_outside_cache_dir() -> Result<(), Error> {
+        let assets = Assets::new(None)
+            .with_cdragon_base_url("http://127.0.0.1:9/")?
+            .with_cache_dir(temp_dir("assets-paths"));
+        for path in [
+            "/lol-game-data/assets/../../../etc/passwd",
+            "../secret.png",
+            "/lol-static-assets/images/..",
+        ] {
+            assert!(matches!(
+                assets.get(&Asset::Path(path.to_owned())).await,
+                Err(Error::InvalidAssetPath(_))
+            ));
+        }
+        assert!(matches!(
+            assets
+                .get(&Asset::Path("/other/image.png".to_owned()))
+                .await,
+            Err(Error::AssetNotFound(_))
+        ));
+        Ok(())
+    }
+}
@@ -81,6 +81,31 @@ impl Client {
             .into()
     }
 
+    /// Client for a stand-in api served over plain http.
+    #[cfg(test)]
+    pub(crate) fn from_base_url(base_url: &str) -> Result<Self, Error> {
+        Ok(Self {
+            base_url: Url::parse(base_url)?,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    #[cfg(feature = "assets")]
+    pub(crate) async fn get_bytes(&self, endpoint: &str) -> Result<Vec<u8>, Error> {
+        log::info!("GET {endpoint}");
+        let mut url = self.base_url.clone();
+        url.set_path(endpoint);
+        Ok(self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?
+            .to_vec())
+    }
+
     pub(crate) async fn post<T: for<'a> Deserialize<'a>, R: Serialize + Sync>(
         &self,
         endpoint: &str,
@@ -123,3 +148,15 @@ impl Client {
         Ok(())
     }
 }
+
+/// Parses a base url that relative paths are joined onto. A missing trailing slash is
+/// added, because joining replaces the last segment of a path without one.
+#[cfg(any(feature = "ddragon", feature = "export", feature = "assets"))]
+pub(crate) fn parse_base_url(base_url: &str) -> Result<Url, Error> {
+    let mut base_url = Url::parse(base_url)?;
+    if !base_url.path().ends_with('/') {
+        let path = format!("{}/", base_url.path());
+        base_url.set_path(&path);
+    }
+    Ok(base_url)
+}
@@ -1,31 +1,326 @@
+use crate::{client::parse_base_url, Error};
+use diff::PatchDiff;
+use reqwest::{StatusCode, Url};
+use serde::de::DeserializeOwned;
+use std::{fs, path::PathBuf};
+use types::{
+    Champion, ChampionDetail, ChampionDetailData, Image, Item, Map, ProfileIcon, Rune, RuneTree,
+    SummonerSpell,
+};
+
+pub mod diff;
+pub mod index;
+pub mod pool;
+
+const DEFAULT_BASE_URL: &str = "https://ddragon.leagueoflegends.com";
+const DEFAULT_LOCALE: &str = "en_US";
+
+/// Data Dragon client with an optional on-disk cache.
+///
+/// Patch specific files are immutable, so they are read from the cache whenever they
+/// exist and only downloaded on a cache miss. The version and language lists are always
+/// refreshed unless the client is offline.
+#[derive(Debug, Clone)]
+pub struct DDragon {
+    base_url: Url,
+    locale: String,
+    cache_dir: Option<PathBuf>,
+    offline: bool,
+    client: reqwest::Client,
+}
+
+impl Default for DDragon {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DDragon {
+    /// Creates a client for the public Data Dragon cdn without any caching.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            base_url: Url::parse(DEFAULT_BASE_URL).expect("default base url is valid"),
+            locale: DEFAULT_LOCALE.to_owned(),
+            cache_dir: None,
+            offline: false,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Uses `base_url` instead of the public Data Dragon cdn. Paths are resolved below
+    /// `base_url`, whether or not it ends in a slash.
+    ///
+    /// # Errors
+    /// Fails if `base_url` is not a valid url
+    pub fn with_base_url(mut self, base_url: &str) -> Result<Self, Error> {
+        self.base_url = parse_base_url(base_url)?;
+        Ok(self)
+    }
+
+    /// Fetches data in `locale`, e.g. `de_DE`, instead of `en_US`. Files that are not
+    /// available in `locale` fall back to `en_US`.
+    #[must_use]
+    pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = locale.into();
+        self
+    }
+
+    /// Locale used for localised data.
+    #[must_use]
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Stores downloaded files in `cache_dir`, mirroring the cdn layout so files are
+    /// keyed by patch and locale.
+    #[must_use]
+    pub fn with_cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(cache_dir.into());
+        self
+    }
+
+    /// Only reads from the cache, never from the network.
+    #[must_use]
+    pub const fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Lists every patch, newest first.
+    ///
+    /// # Errors
+    /// Fails if the cdn cannot be reached, or if offline and the list is not cached
+    pub async fn versions(&self) -> Result<Vec<String>, Error> {
+        self.refreshed("api/versions.json").await
+    }
+
+    /// Newest patch matching the major and minor version of a game version like
+    /// `14.10.587.4312`, or the newest patch if none matches.
+    ///
+    /// # Errors
+    /// Fails if the patch list cannot be fetched or is empty
+    pub async fn patch_for_game_version(&self, game_version: &str) -> Result<String, Error> {
+        let versions = self.versions().await?;
+        let mut parts = game_version.split('.');
+        let prefix = format!(
+            "{}.{}.",
+            parts.next().unwrap_or_default(),
+            parts.next().unwrap_or_default()
+        );
+        versions
+            .iter()
+            .find(|x| x.starts_with(&prefix))
+            .or_else(|| versions.first())
+            .cloned()
+            .ok_or_else(|| Error::Custom("Data Dragon has no versions".into()))
+    }
+
+    /// Lists every locale data is available in.
+    ///
+    /// # Errors
+    /// Fails if the cdn cannot be reached, or if offline and the list is not cached
+    pub async fn languages(&self) -> Result<Vec<String>, Error> {
+        self.refreshed("cdn/languages.json").await
+    }
+
+    /// Summary of every champion in the given patch.
+    ///
+    /// # Errors
+    /// Fails if the file is not cached and cannot be downloaded
+    pub async fn champion(&self, patch: &str) -> Result<Champion, Error> {
+        self.data(patch, "champion.json").await
+    }
+
+    /// Champion changes between the `from` and `to` patches.
+    ///
+    /// # Errors
+    /// Fails if either patch is not cached and cannot be downloaded
+    pub async fn diff_patches(&self, from: &str, to: &str) -> Result<PatchDiff, Error> {
+        let (old, new) = futures::try_join!(self.champion(from), self.champion(to))?;
+        Ok(PatchDiff::new(&old, &new))
+    }
+
+    /// Champion changes from the previous patch to the newest one in [`Self::versions`].
+    ///
+    /// # Errors
+    /// Fails if Data Dragon lists fewer than two patches, or if either patch is not
+    /// cached and cannot be downloaded
+    pub async fn diff_latest_patch(&self) -> Result<PatchDiff, Error> {
+        let versions = self.versions().await?;
+        let [latest, previous, ..] = versions.as_slice() else {
+            return Err(Error::Custom(
+                "Data Dragon lists fewer than two patches".into(),
+            ));
+        };
+        self.diff_patches(previous, latest).await
+    }
+
+    /// Full details of a single champion, including spells and skins. `alias` is the
+    /// key of the champion in [`Champion::data`], e.g. `TwistedFate`.
+    ///
+    /// # Errors
+    /// Fails if the file is not cached and cannot be downloaded
+    pub async fn champion_detail(
+        &self,
+        patch: &str,
+        alias: &str,
+    ) -> Result<ChampionDetailData, Error> {
+        self.data::<ChampionDetail>(patch, &format!("champion/{alias}.json"))
+            .await?
+            .data
+            .remove(alias)
+            .ok_or_else(|| Error::ChampionNotFound(alias.to_owned()))
+    }
+
+    /// Every item in the given patch.
+    ///
+    /// # Errors
+    /// Fails if the file is not cached and cannot be downloaded
+    pub async fn item(&self, patch: &str) -> Result<Item, Error> {
+        self.data(patch, "item.json").await
+    }
+
+    /// Every summoner spell in the given patch.
+    ///
+    /// # Errors
+    /// Fails if the file is not cached and cannot be downloaded
+    pub async fn summoner(&self, patch: &str) -> Result<SummonerSpell, Error> {
+        self.data(patch, "summoner.json").await
+    }
+
+    /// Every rune tree in the given patch.
+    ///
+    /// # Errors
+    /// Fails if the file is not cached and cannot be downloaded
+    pub async fn runes_reforged(&self, patch: &str) -> Result<Vec<RuneTree>, Error> {
+        self.data(patch, "runesReforged.json").await
+    }
+
+    /// Every map in the given patch.
+    ///
+    /// # Errors
+    /// Fails if the file is not cached and cannot be downloaded
+    pub async fn map(&self, patch: &str) -> Result<Map, Error> {
+        self.data(patch, "map.json").await
+    }
+
+    /// Every profile icon in the given patch.
+    ///
+    /// # Errors
+    /// Fails if the file is not cached and cannot be downloaded
+    pub async fn profile_icon(&self, patch: &str) -> Result<ProfileIcon, Error> {
+        self.data(patch, "profileicon.json").await
+    }
+
+    /// Url of the full size image described by `image`.
+    ///
+    /// # Errors
+    /// Fails if the url cannot be constructed
+    pub fn image_url(&self, patch: &str, image: &Image) -> Result<Url, Error> {
+        Ok(self
+            .base_url
+            .join(&format!("cdn/{patch}/img/{}/{}", image.group, image.full))?)
+    }
+
+    /// Url of the icon of a rune or rune tree. Rune icons are not versioned.
+    ///
+    /// # Errors
+    /// Fails if the url cannot be constructed
+    pub fn rune_icon_url(&self, rune: &Rune) -> Result<Url, Error> {
+        Ok(self.base_url.join(&format!("cdn/img/{}", rune.icon))?)
+    }
+
+    async fn data<T: DeserializeOwned>(&self, patch: &str, file: &str) -> Result<T, Error> {
+        let path = |locale: &str| format!("cdn/{patch}/data/{locale}/{file}");
+        match self.cached(&path(&self.locale)).await {
+            Err(e) if self.locale != DEFAULT_LOCALE && is_missing(&e) => {
+                log::warn!(
+                    "{file} is not available in {}, falling back to {DEFAULT_LOCALE}",
+                    self.locale
+                );
+                self.cached(&path(DEFAULT_LOCALE)).await
+            }
+            result => result,
+        }
+    }
+
+    async fn refreshed<T: DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
+        if self.offline {
+            return self.read_cache(path);
+        }
+        self.download(path).await
+    }
+
+    async fn cached<T: DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
+        match self.read_cache(path) {
+            Ok(value) => Ok(value),
+            Err(Error::NotCached(_)) if !self.offline => self.download(path).await,
+            Err(e) => Err(e),
+        }
+    }
+
+    fn read_cache<T: DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
+        let file = self
+            .cache_dir
+            .as_ref()
+            .map(|dir| dir.join(path))
+            .filter(|file| file.is_file())
+            .ok_or_else(|| Error::NotCached(path.to_owned()))?;
+        log::debug!("Reading {} from cache", file.display());
+        Ok(serde_json::from_slice(&fs::read(file)?)?)
+    }
+
+    async fn download<T: DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
+        log::info!("GET {path}");
+        let bytes = self
+            .client
+            .get(self.base_url.join(path)?)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        let value = serde_json::from_slice(&bytes)?;
+        if let Some(dir) = &self.cache_dir {
+            let file = dir.join(path);
+            if let Some(parent) = file.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(file, &bytes)?;
+        }
+        Ok(value)
+    }
+}
+
+/// Data Dragon answers requests for files that do not exist with 403 rather than 404.
+fn is_missing(error: &Error) -> bool {
+    match error {
+        Error::NotCached(_) => true,
+        Error::Request(e) => e.status().is_some_and(|status| {
+            status == StatusCode::NOT_FOUND || status == StatusCode::FORBIDDEN
+        }),
+        _ => false,
+    }
+}
+
 #[allow(clippy::missing_errors_doc)]
 pub mod endpoints {
-    use super::types::Champion;
+    use super::{types::Champion, DDragon};
     use crate::Error;
-    use reqwest;
 
     pub async fn versions() -> Result<Vec<String>, Error> {
-        Ok(
-            reqwest::get("https://ddragon.leagueoflegends.com/api/versions.json")
-                .await?
-                .json()
-                .await?,
-        )
+        DDragon::new().versions().await
     }
 
     pub async fn champion(patch: &str) -> Result<Champion, Error> {
-        Ok(reqwest::get(format!(
-            "https://ddragon.leagueoflegends.com/cdn/{patch}/data/en_US/champion.json"
-        ))
-        .await?
-        .json()
-        .await?)
+        DDragon::new().champion(patch).await
     }
 }
 
 pub mod types {
     use serde::{Deserialize, Serialize};
-    use std::collections::HashMap;
+    use std::{collections::HashMap, convert::Infallible, fmt::Display, str::FromStr};
 
     #[derive(Deserialize, Serialize, Debug, Clone, Default)]
     pub struct Champion {
@@ -45,7 +340,7 @@ pub mod types {
         pub title: String,
         pub blurb: String,
         pub info: ChampionInfo,
-        pub image: ChampionImage,
+        pub image: Image,
         pub tags: Vec<ChampionTag>,
         pub partype: ChampionResource,
         pub stats: ChampionStats,
@@ -59,8 +354,10 @@ pub mod types {
         pub difficulty: u8,
     }
 
+    pub type ChampionImage = Image;
+
     #[derive(Deserialize, Serialize, Debug, Clone, Default)]
-    pub struct ChampionImage {
+    pub struct Image {
         pub full: String,
         pub sprite: String,
         pub group: String,
@@ -70,7 +367,9 @@ pub mod types {
         pub h: u32,
     }
 
-    #[derive(Deserialize, Serialize, Debug, Clone)]
+    /// Champion class. Tags Riot adds in the future are kept as [`ChampionTag::Unknown`].
+    #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+    #[serde(from = "String", into = "String")]
     pub enum ChampionTag {
         Fighter,
         Mage,
@@ -78,13 +377,59 @@ pub mod types {
         Marksman,
         Tank,
         Support,
+        Unknown(String),
     }
 
-    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    impl FromStr for ChampionTag {
+        type Err = Infallible;
+
+        /// Parses a tag case-insensitively.
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(match simplify(s).as_str() {
+                "fighter" => Self::Fighter,
+                "mage" => Self::Mage,
+                "assassin" => Self::Assassin,
+                "marksman" => Self::Marksman,
+                "tank" => Self::Tank,
+                "support" => Self::Support,
+                _ => Self::Unknown(s.to_owned()),
+            })
+        }
+    }
+
+    impl Display for ChampionTag {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(match self {
+                Self::Fighter => "Fighter",
+                Self::Mage => "Mage",
+                Self::Assassin => "Assassin",
+                Self::Marksman => "Marksman",
+                Self::Tank => "Tank",
+                Self::Support => "Support",
+                Self::Unknown(tag) => tag,
+            })
+        }
+    }
+
+    impl From<String> for ChampionTag {
+        fn from(value: String) -> Self {
+            let Ok(tag) = value.parse();
+            tag
+        }
+    }
+
+    impl From<ChampionTag> for String {
+        fn from(value: ChampionTag) -> Self {
+            value.to_string()
+        }
+    }
+
+    /// Resource bar of a champion. Resources Riot adds in the future are kept as
+    /// [`ChampionResource::Unknown`].
+    #[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+    #[serde(from = "String", into = "String")]
     pub enum ChampionResource {
-        #[serde(alias = "")]
         None,
-        #[serde(rename = "Blood Well")]
         BloodWell,
         #[default]
         Mana,
@@ -96,11 +441,80 @@ pub mod types {
         Grit,
         Ferocity,
         Heat,
-        #[serde(rename = "Crimson Rush")]
         CrimsonRush,
         Flow,
+        Unknown(String),
+    }
+
+    impl FromStr for ChampionResource {
+        type Err = Infallible;
+
+        /// Parses a resource case-insensitively, ignoring whitespace. Older patches
+        /// call mana `MP`.
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(match simplify(s).as_str() {
+                "" | "none" => Self::None,
+                "bloodwell" => Self::BloodWell,
+                "mana" | "mp" => Self::Mana,
+                "energy" => Self::Energy,
+                "fury" => Self::Fury,
+                "rage" => Self::Rage,
+                "courage" => Self::Courage,
+                "shield" => Self::Shield,
+                "grit" => Self::Grit,
+                "ferocity" => Self::Ferocity,
+                "heat" => Self::Heat,
+                "crimsonrush" => Self::CrimsonRush,
+                "flow" => Self::Flow,
+                _ => Self::Unknown(s.to_owned()),
+            })
+        }
+    }
+
+    impl Display for ChampionResource {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(match self {
+                Self::None => "None",
+                Self::BloodWell => "Blood Well",
+                Self::Mana => "Mana",
+                Self::Energy => "Energy",
+                Self::Fury => "Fury",
+                Self::Rage => "Rage",
+                Self::Courage => "Courage",
+                Self::Shield => "Shield",
+                Self::Grit => "Grit",
+                Self::Ferocity => "Ferocity",
+                Self::Heat => "Heat",
+                Self::CrimsonRush => "Crimson Rush",
+                Self::Flow => "Flow",
+                Self::Unknown(resource) => resource,
+            })
+        }
+    }
+
+    impl From<String> for ChampionResource {
+        fn from(value: String) -> Self {
+            let Ok(resource) = value.parse();
+            resource
+        }
+    }
+
+    impl From<ChampionResource> for String {
+        fn from(value: ChampionResource) -> Self {
+            value.to_string()
+        }
     }
 
+    fn simplify(s: &str) -> String {
+        s.chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+
+    /// Base stats of a champion. Only the base attack speed changed shape over the
+    /// years: it is `attackspeedoffset` before patch 10.x and `attackspeed` after, so
+    /// each is `None` in patches that do not have it. Every other stat is required.
     #[derive(Deserialize, Serialize, Debug, Clone, Default)]
     pub struct ChampionStats {
         pub hp: f32,
@@ -122,13 +536,390 @@ pub mod types {
         pub attackdamage: f32,
         pub attackdamageperlevel: f32,
         pub attackspeedperlevel: f32,
-        pub attackspeed: f32,
+        pub attackspeed: Option<f32>,
+        pub attackspeedoffset: Option<f32>,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    pub struct ChampionDetail {
+        #[serde(rename = "type")]
+        pub type_: String,
+        pub format: String,
+        pub version: String,
+        pub data: HashMap<String, ChampionDetailData>,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    pub struct ChampionDetailData {
+        pub id: String,
+        pub key: String,
+        pub name: String,
+        pub title: String,
+        pub image: Image,
+        pub skins: Vec<ChampionSkin>,
+        pub lore: String,
+        pub blurb: String,
+        pub allytips: Vec<String>,
+        pub enemytips: Vec<String>,
+        pub tags: Vec<ChampionTag>,
+        pub partype: ChampionResource,
+        pub info: ChampionInfo,
+        pub stats: ChampionStats,
+        pub spells: Vec<ChampionSpell>,
+        pub passive: ChampionPassive,
+        #[serde(default)]
+        pub recommended: Vec<ChampionRecommended>,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    pub struct ChampionSkin {
+        pub id: String,
+        pub num: u32,
+        pub name: String,
+        #[serde(default)]
+        pub chromas: bool,
+    }
+
+    impl ChampionSkin {
+        /// Skin id as used by champ select, e.g. `selected_skin_id`.
+        #[must_use]
+        pub fn skin_id(&self) -> Option<i32> {
+            self.id.parse().ok()
+        }
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ChampionSpell {
+        pub id: String,
+        pub name: String,
+        pub description: String,
+        pub tooltip: String,
+        pub maxrank: u8,
+        pub cooldown: Vec<f32>,
+        pub cooldown_burn: String,
+        pub cost: Vec<f32>,
+        pub cost_burn: String,
+        #[serde(default)]
+        pub cost_type: String,
+        #[serde(default)]
+        pub maxammo: String,
+        pub range: Vec<f32>,
+        pub range_burn: String,
+        pub image: Image,
+        #[serde(default)]
+        pub resource: String,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    pub struct ChampionPassive {
+        pub name: String,
+        pub description: String,
+        pub image: Image,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ChampionRecommended {
+        pub champion: String,
+        pub title: String,
+        pub map: String,
+        pub mode: String,
+        #[serde(rename = "type")]
+        pub type_: String,
+        #[serde(default)]
+        pub blocks: Vec<ChampionRecommendedBlock>,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    pub struct ChampionRecommendedBlock {
+        #[serde(rename = "type")]
+        pub type_: String,
+        pub items: Vec<ChampionRecommendedItem>,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    pub struct ChampionRecommendedItem {
+        pub id: String,
+        pub count: u32,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    pub struct Item {
+        #[serde(rename = "type")]
+        pub type_: String,
+        pub version: String,
+        pub data: HashMap<String, ItemData>,
+    }
+
+    impl Item {
+        /// Looks up an item by the id used in match history, e.g. `item0`.
+        #[must_use]
+        pub fn get(&self, id: u32) -> Option<&ItemData> {
+            self.data.get(&id.to_string())
+        }
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    pub struct ItemData {
+        pub name: String,
+        pub description: String,
+        #[serde(default)]
+        pub colloq: String,
+        #[serde(default)]
+        pub plaintext: String,
+        #[serde(default)]
+        pub into: Vec<String>,
+        #[serde(default)]
+        pub from: Vec<String>,
+        pub image: Image,
+        pub gold: ItemGold,
+        #[serde(default)]
+        pub tags: Vec<String>,
+        pub maps: HashMap<String, bool>,
+        pub stats: HashMap<String, f64>,
+        pub depth: Option<u8>,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    pub struct ItemGold {
+        pub base: u32,
+        pub purchasable: bool,
+        pub total: u32,
+        pub sell: u32,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    pub struct SummonerSpell {
+        #[serde(rename = "type")]
+        pub type_: String,
+        pub version: String,
+        pub data: HashMap<String, SummonerSpellData>,
+    }
+
+    impl SummonerSpell {
+        /// Looks up a summoner spell by the numeric id used in match history, e.g.
+        /// `spell1_id`.
+        #[must_use]
+        pub fn get(&self, id: u32) -> Option<&SummonerSpellData> {
+            let key = id.to_string();
+            self.data.values().find(|spell| spell.key == key)
+        }
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SummonerSpellData {
+        pub id: String,
+        pub name: String,
+        pub description: String,
+        pub tooltip: String,
+        pub maxrank: u8,
+        pub cooldown: Vec<f32>,
+        pub cooldown_burn: String,
+        pub key: String,
+        pub summoner_level: u32,
+        pub modes: Vec<String>,
+        pub range: Vec<f32>,
+        pub image: Image,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    pub struct RuneTree {
+        pub id: i64,
+        pub key: String,
+        pub icon: String,
+        pub name: String,
+        pub slots: Vec<RuneSlot>,
+    }
+
+    impl RuneTree {
+        /// Looks up a rune in any of the given trees by the id used in match history,
+        /// e.g. `perk0`.
+        #[must_use]
+        pub fn find_rune(trees: &[Self], id: i64) -> Option<&Rune> {
+            trees
+                .iter()
+                .flat_map(|tree| &tree.slots)
+                .flat_map(|slot| &slot.runes)
+                .find(|rune| rune.id == id)
+        }
+
+        /// Looks up a rune tree by the id used in match history, e.g.
+        /// `perk_primary_style`.
+        #[must_use]
+        pub fn find_tree(trees: &[Self], id: i64) -> Option<&Self> {
+            trees.iter().find(|tree| tree.id == id)
+        }
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    pub struct RuneSlot {
+        pub runes: Vec<Rune>,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Rune {
+        pub id: i64,
+        pub key: String,
+        pub icon: String,
+        pub name: String,
+        #[serde(default)]
+        pub short_desc: String,
+        #[serde(default)]
+        pub long_desc: String,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    pub struct Map {
+        #[serde(rename = "type")]
+        pub type_: String,
+        pub version: String,
+        pub data: HashMap<String, MapData>,
+    }
+
+    impl Map {
+        /// Looks up a map by the id used in match history, e.g. `map_id`.
+        #[must_use]
+        pub fn get(&self, id: u32) -> Option<&MapData> {
+            self.data.get(&id.to_string())
+        }
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    pub struct MapData {
+        #[serde(rename = "MapName")]
+        pub map_name: String,
+        #[serde(rename = "MapId")]
+        pub map_id: String,
+        pub image: Image,
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    pub struct ProfileIcon {
+        #[serde(rename = "type")]
+        pub type_: String,
+        pub version: String,
+        pub data: HashMap<String, ProfileIconData>,
+    }
+
+    impl ProfileIcon {
+        /// Looks up a profile icon by the id used in match history, e.g.
+        /// `profile_icon`.
+        #[must_use]
+        pub fn get(&self, id: i32) -> Option<&ProfileIconData> {
+            self.data.get(&id.to_string())
+        }
+    }
+
+    #[derive(Deserialize, Serialize, Debug, Clone, Default)]
+    pub struct ProfileIconData {
+        pub id: i32,
+        pub image: Image,
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::ddragon::endpoints::{champion, versions};
+    use super::{
+        types::{Champion, ChampionResource, ChampionStats, ChampionTag, RuneTree},
+        DDragon,
+    };
+    use crate::{
+        ddragon::endpoints::{champion, versions},
+        test_utils::{serve, temp_dir},
+        Error,
+    };
+    use std::{fs, path::Path};
+
+    const CHAMPION_JSON: &str = r#"{"type":"champion","format":"standAloneComplex","version":"14.1.1","data":{"TwistedFate":{"version":"14.1.1","id":"TwistedFate","key":"4","name":"Twisted Fate","title":"the Card Master","blurb":"","info":{"attack":6,"defense":2,"magic":6,"difficulty":9},"image":{"full":"TwistedFate.png","sprite":"champion4.png","group":"champion","x":0,"y":0,"w":48,"h":48},"tags":["Mage"],"partype":"Mana","stats":{"hp":604,"hpperlevel":108,"mp":333,"mpperlevel":39,"movespeed":330,"armor":24,"armorperlevel":4.35,"spellblock":30,"spellblockperlevel":1.3,"attackrange":525,"hpregen":5.5,"hpregenperlevel":0.6,"mpregen":8,"mpregenperlevel":0.8,"crit":0,"critperlevel":0,"attackdamage":52,"attackdamageperlevel":3.3,"attackspeedperlevel":3.22,"attackspeed":0.651}}}}"#;
+
+    /// Checks every unedited `champion.json` saved in `fixtures/ddragon`, named
+    /// `champion-<patch>.json`, so format changes in old patches are caught.
+    #[test]
+    fn historic_champion_fixtures() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/ddragon");
+        let paths: Vec<_> = fs::read_dir(&dir)
+            .unwrap_or_else(|e| panic!("{} cannot be read: {e}", dir.display()))
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|x| x == "json"))
+            .collect();
+        assert!(
+            !paths.is_empty(),
+            "no champion.json fixtures in {}, run fetch.sh there",
+            dir.display()
+        );
+        for path in paths {
+            let champions: Champion = serde_json::from_str(&fs::read_to_string(&path).unwrap())
+                .unwrap_or_else(|e| panic!("{} does not deserialize: {e}", path.display()));
+            for champion in champions.data.values() {
+                assert!(
+                    !matches!(champion.partype, ChampionResource::Unknown(_)),
+                    "{} has unknown resource {} in {}",
+                    champion.name,
+                    champion.partype,
+                    champions.version
+                );
+                assert!(
+                    !champion
+                        .tags
+                        .iter()
+                        .any(|tag| matches!(tag, ChampionTag::Unknown(_))),
+                    "{} has unknown tags in {}",
+                    champion.name,
+                    champions.version
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn stats_before_attackspeed() {
+        let stats: ChampionStats = serde_json::from_str(
+            r#"{"hp":537.8,"hpperlevel":85,"mp":105.6,"mpperlevel":65,"movespeed":345,"armor":18,"armorperlevel":3.5,"spellblock":30,"spellblockperlevel":0,"attackrange":175,"hpregen":8.68,"hpregenperlevel":0.55,"mpregen":6,"mpregenperlevel":0,"crit":0,"critperlevel":0,"attackdamage":54.5,"attackdamageperlevel":3.375,"attackspeedoffset":-0.065,"attackspeedperlevel":3}"#,
+        )
+        .unwrap();
+        assert_eq!(stats.attackspeed, None);
+        assert_eq!(stats.attackspeedoffset, Some(-0.065));
+        assert_eq!(stats.hp, 537.8);
+
+        // Any other missing stat is an error rather than a fake 0
+        assert!(serde_json::from_str::<ChampionStats>(r#"{"hp":537.8}"#).is_err());
+    }
+
+    #[test]
+    fn unknown_tags_and_resources() {
+        let tags: Vec<ChampionTag> =
+            serde_json::from_str(r#"["mage", "SUPPORT", "Specialist"]"#).unwrap();
+        assert_eq!(
+            tags,
+            vec![
+                ChampionTag::Mage,
+                ChampionTag::Support,
+                ChampionTag::Unknown("Specialist".to_owned())
+            ]
+        );
+
+        let resources: Vec<ChampionResource> =
+            serde_json::from_str(r#"["", "MP", "bloodwell", "Crimson Rush", "Overdrive"]"#)
+                .unwrap();
+        assert_eq!(
+            resources,
+            vec![
+                ChampionResource::None,
+                ChampionResource::Mana,
+                ChampionResource::BloodWell,
+                ChampionResource::CrimsonRush,
+                ChampionResource::Unknown("Overdrive".to_owned())
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&resources).unwrap(),
+            r#"["None","Mana","Blood Well","Crimson Rush","Overdrive"]"#
+        );
+    }
 
     #[tokio::test]
     async fn get_champions() {
@@ -137,4 +928,165 @@ mod tests {
         let champions = champion(versions.first().unwrap()).await.unwrap();
         dbg!(champions);
     }
+
+    #[tokio::test]
+    async fn base_url_with_path_prefix() -> Result<(), Error> {
+        let base_url = serve(vec![(
+            "/ddragon/api/versions.json",
+            r#"["14.11.1", "14.10.2"]"#.to_owned(),
+        )]);
+        for base_url in [format!("{base_url}ddragon"), format!("{base_url}ddragon/")] {
+            let ddragon = DDragon::new().with_base_url(&base_url)?;
+            assert_eq!(ddragon.versions().await?, ["14.11.1", "14.10.2"]);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn patch_for_game_version() -> Result<(), Error> {
+        let base_url = serve(vec![(
+            "/api/versions.json",
+            r#"["14.11.1", "14.10.2", "14.10.1", "14.1.1"]"#.to_owned(),
+        )]);
+        let ddragon = DDragon::new().with_base_url(&base_url)?;
+        assert_eq!(
+            ddragon.patch_for_game_version("14.10.587.4312").await?,
+            "14.10.2"
+        );
+        assert_eq!(ddragon.patch_for_game_version("14.1.5").await?, "14.1.1");
+        assert_eq!(ddragon.patch_for_game_version("15.1").await?, "14.11.1");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn offline_reads_from_cache() -> Result<(), Error> {
+        let base_url = serve(vec![
+            ("/api/versions.json", r#"["14.1.1"]"#.to_owned()),
+            (
+                "/cdn/14.1.1/data/en_US/champion.json",
+                CHAMPION_JSON.to_owned(),
+            ),
+        ]);
+        let cache_dir = temp_dir("offline");
+
+        let offline = DDragon::new().with_cache_dir(&cache_dir).offline(true);
+        assert!(matches!(offline.versions().await, Err(Error::NotCached(_))));
+
+        let online = DDragon::new()
+            .with_base_url(&base_url)?
+            .with_cache_dir(&cache_dir);
+        let patch = online.versions().await?.remove(0);
+        online.champion(&patch).await?;
+
+        assert_eq!(offline.versions().await?, vec!["14.1.1"]);
+        let champions = offline.champion(&patch).await?;
+        assert_eq!(champions.data["TwistedFate"].key, "4");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn diff_latest_patch() -> Result<(), Error> {
+        let base_url = serve(vec![
+            ("/api/versions.json", r#"["14.2.1", "14.1.1"]"#.to_owned()),
+            (
+                "/cdn/14.1.1/data/en_US/champion.json",
+                CHAMPION_JSON.to_owned(),
+            ),
+            (
+                "/cdn/14.2.1/data/en_US/champion.json",
+                CHAMPION_JSON
+                    .replace("14.1.1", "14.2.1")
+                    .replace(r#""hp":604"#, r#""hp":630"#),
+            ),
+        ]);
+        let diff = DDragon::new()
+            .with_base_url(&base_url)?
+            .diff_latest_patch()
+            .await?;
+        assert_eq!((diff.from.as_str(), diff.to.as_str()), ("14.1.1", "14.2.1"));
+        assert_eq!(diff.changed[0].stats[0].stat, "hp");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn missing_locale_falls_back_to_english() -> Result<(), Error> {
+        let base_url = serve(vec![
+            ("/cdn/languages.json", r#"["en_US","de_DE"]"#.to_owned()),
+            (
+                "/cdn/14.1.1/data/en_US/champion.json",
+                CHAMPION_JSON.to_owned(),
+            ),
+            (
+                "/cdn/14.1.1/data/de_DE/champion.json",
+                CHAMPION_JSON.replace("the Card Master", "der Kartenmeister"),
+            ),
+        ]);
+
+        let german = DDragon::new()
+            .with_base_url(&base_url)?
+            .with_locale("de_DE");
+        assert!(german.languages().await?.contains(&"de_DE".to_owned()));
+        let champions = german.champion("14.1.1").await?;
+        assert_eq!(champions.data["TwistedFate"].title, "der Kartenmeister");
+
+        let norwegian = DDragon::new()
+            .with_base_url(&base_url)?
+            .with_locale("nb_NO");
+        let champions = norwegian.champion("14.1.1").await?;
+        assert_eq!(champions.data["TwistedFate"].title, "the Card Master");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn champion_detail() -> Result<(), Error> {
+        let base_url = serve(vec![(
+            "/cdn/14.1.1/data/en_US/champion/TwistedFate.json",
+            r#"{"type":"champion","format":"standAloneComplex","version":"14.1.1","data":{"TwistedFate":{"id":"TwistedFate","key":"4","name":"Twisted Fate","title":"the Card Master","image":{"full":"TwistedFate.png","sprite":"champion4.png","group":"champion","x":0,"y":0,"w":48,"h":48},"skins":[{"id":"4000","num":0,"name":"default","chromas":false},{"id":"4001","num":1,"name":"PAX Twisted Fate","chromas":false}],"lore":"","blurb":"","allytips":["Tip"],"enemytips":[],"tags":["Mage"],"partype":"Mana","info":{"attack":6,"defense":2,"magic":6,"difficulty":9},"stats":{"hp":604,"hpperlevel":108,"mp":333,"mpperlevel":39,"movespeed":330,"armor":24,"armorperlevel":4.35,"spellblock":30,"spellblockperlevel":1.3,"attackrange":525,"hpregen":5.5,"hpregenperlevel":0.6,"mpregen":8,"mpregenperlevel":0.8,"crit":0,"critperlevel":0,"attackdamage":52,"attackdamageperlevel":3.3,"attackspeedperlevel":3.22,"attackspeed":0.651},"spells":[{"id":"WildCards","name":"Wild Cards","description":"","tooltip":"","maxrank":5,"cooldown":[6,6,6,6,6],"cooldownBurn":"6","cost":[60,70,80,90,100],"costBurn":"60/70/80/90/100","datavalues":{},"effect":[null],"effectBurn":[null],"vars":[],"costType":" Mana","maxammo":"-1","range":[10000,10000,10000,10000,10000],"rangeBurn":"10000","image":{"full":"WildCards.png","sprite":"spell13.png","group":"spell","x":0,"y":0,"w":48,"h":48},"resource":"{{ abilityresourcename }}"}],"passive":{"name":"Loaded Dice","description":"","image":{"full":"Cardmaster_SealFate.png","sprite":"passive4.png","group":"passive","x":0,"y":0,"w":48,"h":48}},"recommended":[]}}}"#.to_owned(),
+        )]);
+        let ddragon = DDragon::new().with_base_url(&base_url)?;
+
+        let twisted_fate = ddragon.champion_detail("14.1.1", "TwistedFate").await?;
+        assert_eq!(twisted_fate.spells[0].name, "Wild Cards");
+        assert_eq!(twisted_fate.passive.name, "Loaded Dice");
+        assert_eq!(twisted_fate.skins[1].skin_id(), Some(4001));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn resolve_match_history_ids() -> Result<(), Error> {
+        let base_url = serve(vec![
+            (
+                "/cdn/14.1.1/data/en_US/item.json",
+                r#"{"type":"item","version":"14.1.1","basic":{},"data":{"3031":{"name":"Infinity Edge","description":"","colloq":"","plaintext":"","from":["1038"],"image":{"full":"3031.png","sprite":"item1.png","group":"item","x":0,"y":0,"w":48,"h":48},"gold":{"base":1125,"purchasable":true,"total":3400,"sell":2380},"tags":["Damage"],"maps":{"11":true},"stats":{"FlatPhysicalDamageMod":65},"depth":3}},"groups":[],"tree":[]}"#.to_owned(),
+            ),
+            (
+                "/cdn/14.1.1/data/en_US/summoner.json",
+                r#"{"type":"summoner","version":"14.1.1","data":{"SummonerFlash":{"id":"SummonerFlash","name":"Flash","description":"","tooltip":"","maxrank":1,"cooldown":[300],"cooldownBurn":"300","key":"4","summonerLevel":7,"modes":["CLASSIC"],"range":[400],"image":{"full":"SummonerFlash.png","sprite":"spell0.png","group":"spell","x":0,"y":0,"w":48,"h":48}}}}"#.to_owned(),
+            ),
+            (
+                "/cdn/14.1.1/data/en_US/runesReforged.json",
+                r#"[{"id":8000,"key":"Precision","icon":"perk-images/Styles/7201_Precision.png","name":"Precision","slots":[{"runes":[{"id":8005,"key":"PressTheAttack","icon":"perk-images/Styles/Precision/PressTheAttack/PressTheAttack.png","name":"Press the Attack","shortDesc":"","longDesc":""}]}]}]"#.to_owned(),
+            ),
+        ]);
+        let ddragon = DDragon::new().with_base_url(&base_url)?;
+
+        let items = ddragon.item("14.1.1").await?;
+        let infinity_edge = items.get(3031).unwrap();
+        assert_eq!(infinity_edge.name, "Infinity Edge");
+        assert_eq!(
+            ddragon.image_url("14.1.1", &infinity_edge.image)?.path(),
+            "/cdn/14.1.1/img/item/3031.png"
+        );
+
+        let spells = ddragon.summoner("14.1.1").await?;
+        assert_eq!(spells.get(4).unwrap().name, "Flash");
+
+        let runes = ddragon.runes_reforged("14.1.1").await?;
+        assert_eq!(RuneTree::find_tree(&runes, 8000).unwrap().name, "Precision");
+        assert_eq!(
+            RuneTree::find_rune(&runes, 8005).unwrap().name,
+            "Press the Attack"
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,276 @@
+use super::types::{Champion, ChampionData, ChampionResource, ChampionStats, ChampionTag};
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Champion changes between two patches.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct PatchDiff {
+    pub from: String,
+    pub to: String,
+    /// Names of champions only in the newer patch.
+    pub added: Vec<String>,
+    /// Names of champions only in the older patch.
+    pub removed: Vec<String>,
+    pub changed: Vec<ChampionDiff>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ChampionDiff {
+    pub id: String,
+    pub name: String,
+    pub stats: Vec<StatChange>,
+    pub tags_added: Vec<ChampionTag>,
+    pub tags_removed: Vec<ChampionTag>,
+    pub resource: Option<ResourceChange>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct StatChange {
+    pub stat: String,
+    pub from: f32,
+    pub to: f32,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ResourceChange {
+    pub from: ChampionResource,
+    pub to: ChampionResource,
+}
+
+impl PatchDiff {
+    /// Compares the champion summaries of two patches. Champions are sorted by name.
+    #[must_use]
+    pub fn new(from: &Champion, to: &Champion) -> Self {
+        let mut diff = Self {
+            from: from.version.clone(),
+            to: to.version.clone(),
+            ..Default::default()
+        };
+        for (id, new) in &to.data {
+            match from.data.get(id) {
+                Some(old) => {
+                    let champion = ChampionDiff::new(old, new);
+                    if !champion.is_empty() {
+                        diff.changed.push(champion);
+                    }
+                }
+                None => diff.added.push(new.name.clone()),
+            }
+        }
+        diff.removed = from
+            .data
+            .iter()
+            .filter(|(id, _)| !to.data.contains_key(*id))
+            .map(|(_, old)| old.name.clone())
+            .collect();
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changed.sort_by(|a, b| a.name.cmp(&b.name));
+        diff
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Renders the diff as a markdown document with one table per changed champion.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# Champion changes {} → {}\n", self.from, self.to);
+        if self.is_empty() {
+            out.push_str("\nNo changes.\n");
+        }
+        for (title, names) in [
+            ("New champions", &self.added),
+            ("Removed champions", &self.removed),
+        ] {
+            if !names.is_empty() {
+                let _ = write!(out, "\n## {title}\n\n");
+                for name in names {
+                    let _ = writeln!(out, "- {name}");
+                }
+            }
+        }
+        for champion in &self.changed {
+            let _ = write!(out, "\n## {}\n", champion.name);
+            if !champion.stats.is_empty() {
+                let _ = write!(
+                    out,
+                    "\n| Stat | {} | {} | Change |\n| --- | --- | --- | --- |\n",
+                    self.from, self.to
+                );
+                for change in &champion.stats {
+                    let _ = writeln!(
+                        out,
+                        "| {} | {} | {} | {} |",
+                        change.stat,
+                        change.from,
+                        change.to,
+                        format_change(change.to - change.from)
+                    );
+                }
+            }
+            if !champion.tags_added.is_empty() || !champion.tags_removed.is_empty() {
+                let tags: Vec<_> = champion
+                    .tags_added
+                    .iter()
+                    .map(|tag| format!("+{tag}"))
+                    .chain(champion.tags_removed.iter().map(|tag| format!("-{tag}")))
+                    .collect();
+                let _ = write!(out, "\nTags: {}\n", tags.join(", "));
+            }
+            if let Some(resource) = &champion.resource {
+                let _ = write!(out, "\nResource: {} → {}\n", resource.from, resource.to);
+            }
+        }
+        out
+    }
+}
+
+impl ChampionDiff {
+    fn new(old: &ChampionData, new: &ChampionData) -> Self {
+        // Stats missing from either patch are not compared
+        let stats = stat_values(&old.stats)
+            .into_iter()
+            .zip(stat_values(&new.stats))
+            .filter_map(|((stat, from), (_, to))| Some((stat, from?, to?)))
+            .filter(|(_, from, to)| (from - to).abs() > f32::EPSILON)
+            .map(|(stat, from, to)| StatChange {
+                stat: stat.to_owned(),
+                from,
+                to,
+            })
+            .collect();
+        Self {
+            id: new.id.clone(),
+            name: new.name.clone(),
+            stats,
+            tags_added: new
+                .tags
+                .iter()
+                .filter(|tag| !old.tags.contains(tag))
+                .cloned()
+                .collect(),
+            tags_removed: old
+                .tags
+                .iter()
+                .filter(|tag| !new.tags.contains(tag))
+                .cloned()
+                .collect(),
+            resource: (old.partype != new.partype).then(|| ResourceChange {
+                from: old.partype.clone(),
+                to: new.partype.clone(),
+            }),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.stats.is_empty()
+            && self.tags_added.is_empty()
+            && self.tags_removed.is_empty()
+            && self.resource.is_none()
+    }
+}
+
+/// Signed change with at most three decimals, so float noise like `-0.026000023` is
+/// shown as `-0.026`.
+fn format_change(change: f32) -> String {
+    format!("{change:+.3}")
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_owned()
+}
+
+fn stat_values(stats: &ChampionStats) -> [(&'static str, Option<f32>); 21] {
+    [
+        ("hp", Some(stats.hp)),
+        ("hpperlevel", Some(stats.hpperlevel)),
+        ("mp", Some(stats.mp)),
+        ("mpperlevel", Some(stats.mpperlevel)),
+        ("movespeed", Some(stats.movespeed)),
+        ("armor", Some(stats.armor)),
+        ("armorperlevel", Some(stats.armorperlevel)),
+        ("spellblock", Some(stats.spellblock)),
+        ("spellblockperlevel", Some(stats.spellblockperlevel)),
+        ("attackrange", Some(stats.attackrange)),
+        ("hpregen", Some(stats.hpregen)),
+        ("hpregenperlevel", Some(stats.hpregenperlevel)),
+        ("mpregen", Some(stats.mpregen)),
+        ("mpregenperlevel", Some(stats.mpregenperlevel)),
+        ("crit", Some(stats.crit)),
+        ("critperlevel", Some(stats.critperlevel)),
+        ("attackdamage", Some(stats.attackdamage)),
+        ("attackdamageperlevel", Some(stats.attackdamageperlevel)),
+        ("attackspeedperlevel", Some(stats.attackspeedperlevel)),
+        ("attackspeed", stats.attackspeed),
+        ("attackspeedoffset", stats.attackspeedoffset),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_change, PatchDiff};
+    use crate::ddragon::types::{Champion, ChampionData, ChampionResource, ChampionTag};
+
+    fn patch(version: &str, champions: Vec<ChampionData>) -> Champion {
+        Champion {
+            version: version.to_owned(),
+            data: champions.into_iter().map(|c| (c.id.clone(), c)).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn champion(id: &str, hp: f32, tags: Vec<ChampionTag>) -> ChampionData {
+        let mut champion = ChampionData {
+            id: id.to_owned(),
+            name: id.to_owned(),
+            tags,
+            ..Default::default()
+        };
+        champion.stats.hp = hp;
+        champion
+    }
+
+    #[test]
+    fn diff_between_patches() {
+        let old = patch(
+            "14.1.1",
+            vec![
+                champion("Annie", 560.0, vec![ChampionTag::Mage]),
+                champion("Garen", 690.0, vec![ChampionTag::Fighter]),
+                champion("Teemo", 598.0, vec![ChampionTag::Marksman]),
+            ],
+        );
+        let mut garen = champion(
+            "Garen",
+            690.0,
+            vec![ChampionTag::Fighter, ChampionTag::Tank],
+        );
+        garen.partype = ChampionResource::Rage;
+        let new = patch(
+            "14.2.1",
+            vec![
+                champion("Annie", 590.0, vec![ChampionTag::Mage]),
+                garen,
+                champion("Smolder", 575.0, vec![ChampionTag::Marksman]),
+            ],
+        );
+
+        let diff = PatchDiff::new(&old, &new);
+        assert_eq!(diff.added, vec!["Smolder"]);
+        assert_eq!(diff.removed, vec!["Teemo"]);
+        assert_eq!(diff.changed.len(), 2);
+        assert_eq!(diff.changed[0].stats[0].stat, "hp");
+        assert!(diff.changed[1].stats.is_empty());
+        assert!(diff.changed[1].resource.is_some());
+
+        let markdown = diff.to_markdown();
+        assert!(markdown.contains("| hp | 560 | 590 | +30 |"));
+        assert_eq!(format_change(0.625 - 0.651), "-0.026");
+        assert_eq!(format_change(2.5), "+2.5");
+        assert!(markdown.contains("Tags: +Tank"));
+        assert!(markdown.contains("Resource: Mana → Rage"));
+        assert!(serde_json::to_string(&diff).is_ok());
+    }
+}
@@ -0,0 +1,188 @@
+use super::types::{Champion, ChampionData};
+use std::collections::HashMap;
+
+/// Lookup table between the numeric champion ids used by the client api and the
+/// champions in Data Dragon.
+#[derive(Debug, Clone, Default)]
+pub struct ChampionIndex {
+    champions: Vec<ChampionData>,
+    by_id: HashMap<i32, usize>,
+    by_alias: HashMap<String, usize>,
+    by_name: HashMap<String, usize>,
+    by_normalized: HashMap<String, usize>,
+}
+
+impl From<&Champion> for ChampionIndex {
+    fn from(value: &Champion) -> Self {
+        Self::new(value.data.values().cloned())
+    }
+}
+
+impl From<Champion> for ChampionIndex {
+    fn from(value: Champion) -> Self {
+        Self::new(value.data.into_values())
+    }
+}
+
+impl ChampionIndex {
+    pub fn new(champions: impl IntoIterator<Item = ChampionData>) -> Self {
+        let mut index = Self {
+            champions: champions.into_iter().collect(),
+            ..Default::default()
+        };
+        for (i, champion) in index.champions.iter().enumerate() {
+            if let Ok(id) = champion.key.parse() {
+                index.by_id.insert(id, i);
+            }
+            index.by_alias.insert(champion.id.clone(), i);
+            index.by_name.insert(champion.name.clone(), i);
+            index.by_normalized.insert(normalize(&champion.id), i);
+            index.by_normalized.insert(normalize(&champion.name), i);
+        }
+        index
+    }
+
+    /// Every champion in the index, in no particular order.
+    pub fn champions(&self) -> impl Iterator<Item = &ChampionData> {
+        self.champions.iter()
+    }
+
+    /// Looks up a champion by numeric id, e.g. `4`.
+    #[must_use]
+    pub fn by_id(&self, id: i32) -> Option<&ChampionData> {
+        self.by_id.get(&id).map(|&i| &self.champions[i])
+    }
+
+    /// Looks up a champion by alias, e.g. `TwistedFate`.
+    #[must_use]
+    pub fn by_alias(&self, alias: &str) -> Option<&ChampionData> {
+        self.by_alias.get(alias).map(|&i| &self.champions[i])
+    }
+
+    /// Looks up a champion by display name, e.g. `Twisted Fate`.
+    #[must_use]
+    pub fn by_name(&self, name: &str) -> Option<&ChampionData> {
+        self.by_name.get(name).map(|&i| &self.champions[i])
+    }
+
+    /// Display name of the champion with the given id, falling back to the id itself.
+    #[must_use]
+    pub fn name(&self, id: i32) -> String {
+        self.by_id(id)
+            .map_or_else(|| id.to_string(), |champion| champion.name.clone())
+    }
+
+    /// Looks up a champion from user input.
+    ///
+    /// Tries, in order, a numeric id, an exact alias or name, a case and punctuation
+    /// insensitive alias or name, a unique prefix and finally the closest name within
+    /// a small edit distance.
+    #[must_use]
+    pub fn find(&self, query: &str) -> Option<&ChampionData> {
+        let query = query.trim();
+        if let Ok(id) = query.parse() {
+            return self.by_id(id);
+        }
+        if let Some(champion) = self.by_alias(query).or_else(|| self.by_name(query)) {
+            return Some(champion);
+        }
+
+        let normalized = normalize(query);
+        if normalized.is_empty() {
+            return None;
+        }
+        if let Some(&i) = self.by_normalized.get(&normalized) {
+            return Some(&self.champions[i]);
+        }
+
+        let mut prefix_matches = self
+            .by_normalized
+            .iter()
+            .filter(|(key, _)| key.starts_with(&normalized))
+            .map(|(_, &i)| i);
+        if let Some(first) = prefix_matches.next() {
+            if prefix_matches.all(|i| i == first) {
+                return Some(&self.champions[first]);
+            }
+        }
+
+        let max_distance = (normalized.len() / 3).max(1);
+        self.by_normalized
+            .iter()
+            .map(|(key, &i)| (edit_distance(key, &normalized), i))
+            .filter(|&(distance, _)| distance <= max_distance)
+            .min_by_key(|&(distance, i)| (distance, &self.champions[i].name))
+            .map(|(_, i)| &self.champions[i])
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<_> = b.chars().collect();
+    let mut previous: Vec<_> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChampionIndex;
+    use crate::ddragon::types::ChampionData;
+
+    fn champion(key: &str, id: &str, name: &str) -> ChampionData {
+        ChampionData {
+            key: key.to_owned(),
+            id: id.to_owned(),
+            name: name.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    fn index() -> ChampionIndex {
+        ChampionIndex::new([
+            champion("4", "TwistedFate", "Twisted Fate"),
+            champion("145", "Kaisa", "Kai'Sa"),
+            champion("20", "Nunu", "Nunu & Willump"),
+            champion("62", "MonkeyKing", "Wukong"),
+            champion("11", "MasterYi", "Master Yi"),
+            champion("21", "MissFortune", "Miss Fortune"),
+        ])
+    }
+
+    #[test]
+    fn exact_lookups() {
+        let index = index();
+        assert_eq!(index.by_id(4).unwrap().name, "Twisted Fate");
+        assert_eq!(index.by_alias("MonkeyKing").unwrap().name, "Wukong");
+        assert_eq!(index.by_name("Wukong").unwrap().key, "62");
+        assert_eq!(index.find("62").unwrap().name, "Wukong");
+        assert_eq!(index.name(9999), "9999");
+    }
+
+    #[test]
+    fn forgiving_lookups() {
+        let index = index();
+        assert_eq!(index.find("twisted fate").unwrap().key, "4");
+        assert_eq!(index.find("kaisa").unwrap().key, "145");
+        assert_eq!(index.find("nunu and willump").unwrap().key, "20");
+        assert_eq!(index.find("nunu").unwrap().key, "20");
+        assert_eq!(index.find("wu").unwrap().key, "62");
+        assert_eq!(index.find("twisted fat").unwrap().key, "4");
+        assert_eq!(index.find("twsited fate").unwrap().key, "4");
+        assert!(index.find("m").is_none());
+        assert!(index.find("teemo").is_none());
+    }
+}
@@ -0,0 +1,282 @@
+use super::types::{ChampionData, ChampionTag};
+use crate::Error;
+use itertools::Itertools;
+use rand::{seq::SliceRandom, Rng};
+use std::fmt::Write;
+
+/// Champions with a shorter attack range than this are considered melee.
+const MELEE_RANGE: f32 = 300.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PoolMode {
+    /// Every player gets their own options.
+    #[default]
+    PerPlayer,
+    /// Every team shares one pool with the same number of options per player.
+    PerTeam,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttackType {
+    Melee,
+    Ranged,
+}
+
+/// Filters and sizes for [`generate_pools`].
+#[derive(Debug, Clone)]
+pub struct PoolOptions {
+    pub mode: PoolMode,
+    pub options_per_player: usize,
+    /// Never offer the same champion to both teams.
+    pub unique_across_teams: bool,
+    pub attack_type: Option<AttackType>,
+    /// Only champions with at least one of these tags, if not empty.
+    pub tags: Vec<ChampionTag>,
+    /// Only champions with at most this `info.difficulty`.
+    pub max_difficulty: Option<u8>,
+}
+
+impl Default for PoolOptions {
+    fn default() -> Self {
+        Self {
+            mode: PoolMode::default(),
+            options_per_player: 3,
+            unique_across_teams: true,
+            attack_type: None,
+            tags: Vec::new(),
+            max_difficulty: None,
+        }
+    }
+}
+
+impl PoolOptions {
+    fn allows(&self, champion: &ChampionData) -> bool {
+        let attack_type_allowed = match self.attack_type {
+            Some(AttackType::Melee) => champion.stats.attackrange < MELEE_RANGE,
+            Some(AttackType::Ranged) => champion.stats.attackrange >= MELEE_RANGE,
+            None => true,
+        };
+        attack_type_allowed
+            && (self.tags.is_empty() || champion.tags.iter().any(|tag| self.tags.contains(tag)))
+            && self
+                .max_difficulty
+                .is_none_or(|max| champion.info.difficulty <= max)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PlayerPool<'a> {
+    pub player: String,
+    pub champions: Vec<&'a ChampionData>,
+}
+
+/// Champion options for one team. In [`PoolMode::PerTeam`] the options are in `shared`
+/// and every player pool is empty.
+#[derive(Debug, Clone)]
+pub struct TeamPool<'a> {
+    pub players: Vec<PlayerPool<'a>>,
+    pub shared: Vec<&'a ChampionData>,
+}
+
+/// Draws random champions for every player in `teams`.
+///
+/// # Errors
+/// Fails if fewer champions match the filters than are needed
+pub fn generate_pools<'a, R: Rng + ?Sized>(
+    champions: impl IntoIterator<Item = &'a ChampionData>,
+    teams: &[Vec<String>],
+    options: &PoolOptions,
+    rng: &mut R,
+) -> Result<Vec<TeamPool<'a>>, Error> {
+    let mut available: Vec<_> = champions
+        .into_iter()
+        .filter(|champion| options.allows(champion))
+        .collect();
+    available.shuffle(rng);
+
+    let team_sizes = teams
+        .iter()
+        .map(|team| team.len() * options.options_per_player);
+    let needed = if options.unique_across_teams {
+        team_sizes.sum()
+    } else {
+        team_sizes.max().unwrap_or_default()
+    };
+    if available.len() < needed {
+        return Err(Error::NotEnoughChampions);
+    }
+
+    let mut drawn = available.iter().copied();
+    let mut pools = Vec::with_capacity(teams.len());
+    for team in teams {
+        if !options.unique_across_teams {
+            available.shuffle(rng);
+            drawn = available.iter().copied();
+        }
+        let mut team_pool = TeamPool {
+            players: Vec::with_capacity(team.len()),
+            shared: Vec::new(),
+        };
+        for player in team {
+            let champions: Vec<_> = drawn.by_ref().take(options.options_per_player).collect();
+            match options.mode {
+                PoolMode::PerPlayer => team_pool.players.push(PlayerPool {
+                    player: player.clone(),
+                    champions,
+                }),
+                PoolMode::PerTeam => {
+                    team_pool.shared.extend(champions);
+                    team_pool.players.push(PlayerPool {
+                        player: player.clone(),
+                        champions: Vec::new(),
+                    });
+                }
+            }
+        }
+        pools.push(team_pool);
+    }
+    Ok(pools)
+}
+
+/// Formats pools for the lobby chat, in the same layout as the team announcement.
+#[must_use]
+pub fn format_pools(pools: &[TeamPool]) -> String {
+    #[allow(unstable_name_collisions)]
+    let teams: String = pools
+        .iter()
+        .enumerate()
+        .map(|(i, team)| {
+            let mut out = format!("Team {}:", i + 1);
+            if team.shared.is_empty() {
+                for player in &team.players {
+                    let _ = write!(
+                        out,
+                        "\n{}: {}",
+                        player.player,
+                        player.champions.iter().map(|c| &c.name).join(", ")
+                    );
+                }
+            } else {
+                let _ = write!(
+                    out,
+                    "\n{}\n{}",
+                    team.players.iter().map(|p| &p.player).join(", "),
+                    team.shared.iter().map(|c| &c.name).join(", ")
+                );
+            }
+            out
+        })
+        .intersperse("\n----------\n".into())
+        .collect();
+    format!(".\n{teams}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_pools, generate_pools, AttackType, PoolMode, PoolOptions};
+    use crate::{
+        ddragon::types::{ChampionData, ChampionTag},
+        Error,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::collections::HashSet;
+
+    fn champions() -> Vec<ChampionData> {
+        (0..20)
+            .map(|i| {
+                let mut champion = ChampionData {
+                    key: i.to_string(),
+                    name: format!("Champion {i}"),
+                    tags: vec![if i % 2 == 0 {
+                        ChampionTag::Fighter
+                    } else {
+                        ChampionTag::Mage
+                    }],
+                    ..Default::default()
+                };
+                champion.stats.attackrange = if i < 10 { 125.0 } else { 550.0 };
+                champion
+            })
+            .collect()
+    }
+
+    fn teams() -> Vec<Vec<String>> {
+        vec![vec!["a".into(), "b".into()], vec!["c".into(), "d".into()]]
+    }
+
+    #[test]
+    fn unique_per_player_pools() -> Result<(), Error> {
+        let champions = champions();
+        let options = PoolOptions {
+            options_per_player: 2,
+            attack_type: Some(AttackType::Melee),
+            ..Default::default()
+        };
+        let pools = generate_pools(
+            &champions,
+            &teams(),
+            &options,
+            &mut StdRng::seed_from_u64(1),
+        )?;
+
+        let drawn: Vec<_> = pools
+            .iter()
+            .flat_map(|team| &team.players)
+            .flat_map(|player| &player.champions)
+            .collect();
+        assert_eq!(drawn.len(), 8);
+        assert_eq!(
+            drawn.iter().map(|c| &c.key).collect::<HashSet<_>>().len(),
+            8
+        );
+        assert!(drawn.iter().all(|c| c.stats.attackrange < 300.0));
+
+        let message = format_pools(&pools);
+        assert!(message.starts_with(".\nTeam 1:\na: "));
+        assert!(message.contains("\n----------\nTeam 2:\nc: "));
+        Ok(())
+    }
+
+    #[test]
+    fn shared_team_pools() -> Result<(), Error> {
+        let champions = champions();
+        let options = PoolOptions {
+            mode: PoolMode::PerTeam,
+            options_per_player: 3,
+            unique_across_teams: false,
+            tags: vec![ChampionTag::Mage],
+            ..Default::default()
+        };
+        let pools = generate_pools(
+            &champions,
+            &teams(),
+            &options,
+            &mut StdRng::seed_from_u64(1),
+        )?;
+        assert!(pools.iter().all(|team| team.shared.len() == 6));
+        assert!(pools
+            .iter()
+            .flat_map(|team| &team.shared)
+            .all(|c| c.tags == vec![ChampionTag::Mage]));
+        Ok(())
+    }
+
+    #[test]
+    fn not_enough_champions() {
+        let champions = champions();
+        let options = PoolOptions {
+            options_per_player: 3,
+            attack_type: Some(AttackType::Ranged),
+            ..Default::default()
+        };
+        assert!(matches!(
+            generate_pools(
+                &champions,
+                &teams(),
+                &options,
+                &mut StdRng::seed_from_u64(1)
+            ),
+            Err(Error::NotEnoughChampions)
+        ));
+    }
+}
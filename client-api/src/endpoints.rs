@@ -1,13 +1,17 @@
 #![allow(clippy::missing_errors_doc)]
+pub mod match_history;
+
 use std::collections::HashMap;
 
 use crate::{
     client::Client,
     types::{
         LolChallengesUIChallenge, LolChampSelectChampSelectAction,
-        LolChampSelectChampSelectPlayerSelection, LolChampSelectChampSelectSession,
+        LolChampSelectChampSelectMySelection, LolChampSelectChampSelectPlayerSelection,
+        LolChampSelectChampSelectSession, LolChampSelectLegacyChampSelectSession,
         LolChampionMasteryChampionMastery, LolChatConversationMessageResource,
-        LolChatConversationResource, LolChatFriendResource, LolLobbyGameModeDto,
+        LolChatConversationResource, LolChatFriendResource, LolEndOfGameEndOfGameStats,
+        LolGameflowGameflowPhase, LolGameflowGameflowSession, LolLobbyGameModeDto,
         LolLobbyLobbyChangeGameDto, LolLobbyLobbyDto, LolLobbyLobbyInvitationDto,
         LolLobbySubteamDataDto, LolMatchHistoryMatchHistoryGame, LolMatchHistoryMatchHistoryList,
     },
@@ -58,8 +62,8 @@ impl Client {
 
     pub async fn get_lol_match_history_v1_products_lol_current_summoner_matches(
         &self,
-        beg_index: Option<u8>,
-        end_index: Option<u8>,
+        beg_index: Option<u32>,
+        end_index: Option<u32>,
     ) -> Result<LolMatchHistoryMatchHistoryList, Error> {
         // let params = v
         self.get(
@@ -69,6 +73,19 @@ impl Client {
         .await
     }
 
+    pub async fn get_lol_match_history_v1_products_lol_by_puuid_matches(
+        &self,
+        puuid: &str,
+        beg_index: Option<u32>,
+        end_index: Option<u32>,
+    ) -> Result<LolMatchHistoryMatchHistoryList, Error> {
+        self.get(
+            &format!("/lol-match-history/v1/products/lol/{puuid}/matches"),
+            &Some(&[("begIndex", beg_index), ("endIndex", end_index)]),
+        )
+        .await
+    }
+
     pub async fn get_lol_match_history_v1_games_by_game_id(
         &self,
         game_id: u64,
@@ -80,6 +97,24 @@ impl Client {
         .await
     }
 
+    pub async fn get_lol_end_of_game_v1_eog_stats_block(
+        &self,
+    ) -> Result<LolEndOfGameEndOfGameStats, Error> {
+        self.get("/lol-end-of-game/v1/eog-stats-block", &None::<()>)
+            .await
+    }
+
+    pub async fn get_lol_gameflow_v1_gameflow_phase(
+        &self,
+    ) -> Result<LolGameflowGameflowPhase, Error> {
+        self.get("/lol-gameflow/v1/gameflow-phase", &None::<()>)
+            .await
+    }
+
+    pub async fn get_lol_gameflow_v1_session(&self) -> Result<LolGameflowGameflowSession, Error> {
+        self.get("/lol-gameflow/v1/session", &None::<()>).await
+    }
+
     pub async fn get_lol_lobby_v1_parties_gamemode(&self) -> Result<LolLobbyGameModeDto, Error> {
         self.get("/lol-lobby/v1/parties/gamemode", &None::<()>)
             .await
@@ -116,6 +151,13 @@ impl Client {
         self.get("/lol-champ-select/v1/session", &None::<()>).await
     }
 
+    pub async fn get_lol_champ_select_legacy_v1_session(
+        &self,
+    ) -> Result<LolChampSelectLegacyChampSelectSession, Error> {
+        self.get("/lol-champ-select-legacy/v1/session", &None::<()>)
+            .await
+    }
+
     pub async fn get_lol_champ_select_v1_session_my_selection(
         &self,
     ) -> Result<LolChampSelectChampSelectPlayerSelection, Error> {
@@ -123,6 +165,14 @@ impl Client {
             .await
     }
 
+    pub async fn patch_lol_champ_select_v1_session_my_selection(
+        &self,
+        body: LolChampSelectChampSelectMySelection,
+    ) -> Result<(), Error> {
+        self.patch_empty_response("/lol-champ-select/v1/session/my-selection", &body)
+            .await
+    }
+
     pub async fn patch_lol_champ_select_v1_session_actions_by_id(
         &self,
         id: i64,
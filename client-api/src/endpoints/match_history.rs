@@ -0,0 +1,362 @@
+use std::{
+    collections::VecDeque,
+    future::Future,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures::{stream, Stream};
+
+use crate::{
+    client::Client,
+    types::{LolMatchHistoryMatchHistoryGame, LolMatchHistoryMatchHistoryList},
+    Error,
+};
+
+const PAGE_SIZE: u32 = 50;
+
+/// Filters for [`Client::match_history`]. Every filter that is set must match for a
+/// game to be yielded.
+#[derive(Debug, Clone, Default)]
+pub struct MatchHistoryFilter {
+    pub queue_id: Option<i32>,
+    pub map_id: Option<u32>,
+    pub game_type: Option<String>,
+    pub game_mode: Option<String>,
+    /// Only games created at or after this time.
+    pub after: Option<SystemTime>,
+    /// Only games created before this time.
+    pub before: Option<SystemTime>,
+    /// Only games with at least this many participants. Match history pages only list
+    /// the current player, so every game passing the other filters is fetched in full.
+    pub min_participants: Option<usize>,
+    /// Number of match history entries to look through, regardless of how many match.
+    pub limit: Option<u32>,
+}
+
+impl MatchHistoryFilter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Classic 5v5 custom games on Summoner's Rift.
+    #[must_use]
+    pub fn custom_games() -> Self {
+        Self::new()
+            .map(11)
+            .game_type("CUSTOM_GAME")
+            .game_mode("CLASSIC")
+            .min_participants(10)
+    }
+
+    #[must_use]
+    pub fn queue(mut self, queue_id: i32) -> Self {
+        self.queue_id = Some(queue_id);
+        self
+    }
+
+    #[must_use]
+    pub fn map(mut self, map_id: u32) -> Self {
+        self.map_id = Some(map_id);
+        self
+    }
+
+    #[must_use]
+    pub fn game_type(mut self, game_type: &str) -> Self {
+        self.game_type = Some(game_type.to_owned());
+        self
+    }
+
+    #[must_use]
+    pub fn game_mode(mut self, game_mode: &str) -> Self {
+        self.game_mode = Some(game_mode.to_owned());
+        self
+    }
+
+    #[must_use]
+    pub fn after(mut self, time: SystemTime) -> Self {
+        self.after = Some(time);
+        self
+    }
+
+    #[must_use]
+    pub fn before(mut self, time: SystemTime) -> Self {
+        self.before = Some(time);
+        self
+    }
+
+    #[must_use]
+    pub fn min_participants(mut self, min_participants: usize) -> Self {
+        self.min_participants = Some(min_participants);
+        self
+    }
+
+    #[must_use]
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Checks every filter except `min_participants` and `limit`.
+    #[must_use]
+    pub fn matches(&self, game: &LolMatchHistoryMatchHistoryGame) -> bool {
+        let created = created_at(game);
+        self.queue_id.is_none_or(|x| x == game.queue_id)
+            && self.map_id.is_none_or(|x| x == game.map_id)
+            && self.game_type.as_ref().is_none_or(|x| *x == game.game_type)
+            && self.game_mode.as_ref().is_none_or(|x| *x == game.game_mode)
+            && self.after.is_none_or(|x| created >= x)
+            && self.before.is_none_or(|x| created < x)
+    }
+
+    /// Match history is newest first, so nothing after `game` can match.
+    fn is_exhausted_by(&self, game: &LolMatchHistoryMatchHistoryGame) -> bool {
+        self.after.is_some_and(|x| created_at(game) < x)
+    }
+}
+
+fn created_at(game: &LolMatchHistoryMatchHistoryGame) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(game.game_creation)
+}
+
+impl Client {
+    /// Pages through the current summoner's whole match history, newest first, yielding
+    /// the games that match `filter`.
+    ///
+    /// Games are match history summaries, unless `filter.min_participants` is set, in
+    /// which case they are the full games.
+    pub fn match_history(
+        &self,
+        filter: MatchHistoryFilter,
+    ) -> impl Stream<Item = Result<LolMatchHistoryMatchHistoryGame, Error>> + '_ {
+        paginate(self, filter, move |beg_index, end_index| {
+            self.get_lol_match_history_v1_products_lol_current_summoner_matches(
+                Some(beg_index),
+                Some(end_index),
+            )
+        })
+    }
+
+    /// Like [`Client::match_history`], but for the player with the given puuid, such as a
+    /// friend or someone from a previous game.
+    pub fn match_history_by_puuid<'a>(
+        &'a self,
+        puuid: &'a str,
+        filter: MatchHistoryFilter,
+    ) -> impl Stream<Item = Result<LolMatchHistoryMatchHistoryGame, Error>> + 'a {
+        paginate(self, filter, move |beg_index, end_index| {
+            self.get_lol_match_history_v1_products_lol_by_puuid_matches(
+                puuid,
+                Some(beg_index),
+                Some(end_index),
+            )
+        })
+    }
+}
+
+struct Pages<F> {
+    fetch_page: F,
+    filter: MatchHistoryFilter,
+    beg_index: u32,
+    pending: VecDeque<LolMatchHistoryMatchHistoryGame>,
+    done: bool,
+}
+
+fn paginate<'a, F, Fut>(
+    client: &'a Client,
+    filter: MatchHistoryFilter,
+    fetch_page: F,
+) -> impl Stream<Item = Result<LolMatchHistoryMatchHistoryGame, Error>> + 'a
+where
+    F: Fn(u32, u32) -> Fut + 'a,
+    Fut: Future<Output = Result<LolMatchHistoryMatchHistoryList, Error>> + 'a,
+{
+    let pages = Pages {
+        fetch_page,
+        filter,
+        beg_index: 0,
+        pending: VecDeque::new(),
+        done: false,
+    };
+
+    stream::try_unfold(pages, move |mut pages| async move {
+        loop {
+            if let Some(game) = pages.pending.pop_front() {
+                if pages.filter.is_exhausted_by(&game) {
+                    pages.pending.clear();
+                    pages.done = true;
+                    continue;
+                }
+                if !pages.filter.matches(&game) {
+                    continue;
+                }
+                let Some(min_participants) = pages.filter.min_participants else {
+                    return Ok(Some((game, pages)));
+                };
+                let game = client
+                    .get_lol_match_history_v1_games_by_game_id(game.game_id)
+                    .await?;
+                if game.participants.len() >= min_participants {
+                    return Ok(Some((game, pages)));
+                }
+                continue;
+            }
+            if pages.done {
+                return Ok(None);
+            }
+
+            let mut end_index = pages.beg_index + PAGE_SIZE;
+            if let Some(limit) = pages.filter.limit {
+                end_index = end_index.min(limit);
+            }
+            if pages.beg_index >= end_index {
+                return Ok(None);
+            }
+            let page = (pages.fetch_page)(pages.beg_index, end_index)
+                .await?
+                .games
+                .games;
+            pages.done = page.len() < (end_index - pages.beg_index) as usize;
+            pages.beg_index = end_index;
+            pages.pending.extend(page);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MatchHistoryFilter;
+    use crate::{
+        client::Client,
+        test_utils::serve_recording,
+        types::{
+            LolMatchHistoryMatchHistoryGame, LolMatchHistoryMatchHistoryList,
+            LolMatchHistoryMatchHistoryParticipant,
+        },
+        Error,
+    };
+    use futures::TryStreamExt;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    const MATCHES: &str = "/lol-match-history/v1/products/lol/current-summoner/matches";
+
+    fn game(game_id: u64, queue_id: i32, participants: usize) -> LolMatchHistoryMatchHistoryGame {
+        LolMatchHistoryMatchHistoryGame {
+            game_id,
+            game_creation: game_id * 1000,
+            queue_id,
+            participants: vec![LolMatchHistoryMatchHistoryParticipant::default(); participants],
+            ..Default::default()
+        }
+    }
+
+    fn page(game_ids: impl Iterator<Item = u64>) -> String {
+        let mut list = LolMatchHistoryMatchHistoryList::default();
+        list.games.games = game_ids.map(|x| game(x, (x % 2) as i32, 1)).collect();
+        serde_json::to_string(&list).unwrap()
+    }
+
+    #[tokio::test]
+    async fn pages_through_history() -> Result<(), Error> {
+        // 120 games, newest first
+        let (base_url, requests) = serve_recording(vec![
+            (
+                "/lol-match-history/v1/products/lol/current-summoner/matches?begIndex=0&endIndex=50",
+                page((71..=120).rev()),
+            ),
+            (
+                "/lol-match-history/v1/products/lol/current-summoner/matches?begIndex=50&endIndex=100",
+                page((21..=70).rev()),
+            ),
+            (
+                "/lol-match-history/v1/products/lol/current-summoner/matches?begIndex=100&endIndex=150",
+                page((1..=20).rev()),
+            ),
+            (MATCHES, page(0..0)),
+        ]);
+        let client = Client::from_base_url(&base_url)?;
+
+        let games: Vec<_> = client
+            .match_history(MatchHistoryFilter::new())
+            .try_collect()
+            .await?;
+        assert_eq!(games.len(), 120);
+        assert_eq!(requests.lock().unwrap().len(), 3);
+
+        let games: Vec<_> = client
+            .match_history(
+                MatchHistoryFilter::new()
+                    .queue(1)
+                    .after(UNIX_EPOCH + Duration::from_secs(60))
+                    .before(UNIX_EPOCH + Duration::from_secs(100)),
+            )
+            .try_collect()
+            .await?;
+        let game_ids: Vec<_> = games.iter().map(|x| x.game_id).collect();
+        assert_eq!(game_ids, (61..100).rev().step_by(2).collect::<Vec<_>>());
+        // Stops after the page that goes past the start of the date range
+        assert_eq!(requests.lock().unwrap().len(), 5);
+
+        let games: Vec<_> = client
+            .match_history(MatchHistoryFilter::new().limit(10))
+            .try_collect()
+            .await?;
+        assert!(games.is_empty());
+        assert!(requests.lock().unwrap()[5]
+            .path
+            .ends_with("?begIndex=0&endIndex=10"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fetches_full_games_for_participant_count() -> Result<(), Error> {
+        let (base_url, _) = serve_recording(vec![
+            (MATCHES, page((1..=3).rev())),
+            (
+                "/lol-match-history/v1/games/3",
+                serde_json::to_string(&game(3, 1, 10)).unwrap(),
+            ),
+            (
+                "/lol-match-history/v1/games/2",
+                serde_json::to_string(&game(2, 0, 6)).unwrap(),
+            ),
+            (
+                "/lol-match-history/v1/games/1",
+                serde_json::to_string(&game(1, 1, 10)).unwrap(),
+            ),
+        ]);
+        let client = Client::from_base_url(&base_url)?;
+
+        let games: Vec<_> = client
+            .match_history(MatchHistoryFilter::new().min_participants(10))
+            .try_collect()
+            .await?;
+        let game_ids: Vec<_> = games.iter().map(|x| x.game_id).collect();
+        assert_eq!(game_ids, [3, 1]);
+        assert!(games.iter().all(|x| x.participants.len() == 10));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn pages_through_other_players_history() -> Result<(), Error> {
+        let (base_url, requests) = serve_recording(vec![
+            (
+                "/lol-match-history/v1/products/lol/some-puuid/matches?begIndex=0&endIndex=50",
+                page((51..=100).rev()),
+            ),
+            (
+                "/lol-match-history/v1/products/lol/some-puuid/matches?begIndex=50&endIndex=75",
+                page((26..=50).rev()),
+            ),
+        ]);
+        let client = Client::from_base_url(&base_url)?;
+
+        let games: Vec<_> = client
+            .match_history_by_puuid("some-puuid", MatchHistoryFilter::new().queue(0).limit(75))
+            .try_collect()
+            .await?;
+        assert_eq!(games.len(), 38);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        Ok(())
+    }
+}
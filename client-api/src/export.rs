@@ -0,0 +1,562 @@
+use crate::{
+    client::parse_base_url,
+    types::{
+        LolMatchHistoryMatchHistoryGame, LolMatchHistoryMatchHistoryParticipant,
+        LolMatchHistoryMatchHistoryParticipantIdentities,
+    },
+    Error,
+};
+use async_std::task::spawn_blocking;
+use futures::future::{join_all, BoxFuture};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteSink;
+
+const DEFAULT_BASE_URL: &str = "https://api.påsan.com/";
+
+/// Destination for exported games.
+pub trait MatchSink: Send {
+    /// Human readable name used in logs.
+    fn name(&self) -> &str;
+
+    /// Exports `games`, returning the outcome for every game in the same order as
+    /// `games`.
+    fn send<'a>(
+        &'a mut self,
+        games: &'a [LolMatchHistoryMatchHistoryGame],
+    ) -> BoxFuture<'a, Vec<Result<(), Error>>>;
+}
+
+/// Progress of incremental exports, persisted between runs.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncState {
+    /// Games every sink has accepted.
+    pub exported: BTreeSet<u64>,
+    /// Games at least one sink rejected, with the positions of the sinks that rejected
+    /// them. Only those sinks get the game again on the next sync.
+    pub failed: BTreeMap<u64, BTreeSet<usize>>,
+    /// Creation time of the newest game seen by the last completed sync. Older games
+    /// are not fetched again unless they failed.
+    pub newest_game_creation: u64,
+    /// Set while a sync is unfinished because it stopped at its game limit: creation time
+    /// of the oldest game walked so far. The next sync continues below it.
+    #[serde(default)]
+    pub resume_before: Option<u64>,
+    /// Creation time of the newest game seen by the unfinished sync. It becomes
+    /// `newest_game_creation` once the walk reaches the previous sync or the end of the
+    /// match history.
+    #[serde(default)]
+    pub pending_newest_game_creation: u64,
+}
+
+impl SyncState {
+    /// Loads the state saved at `path`, or an empty state if the file does not exist.
+    ///
+    /// # Errors
+    /// Fails if the file exists but cannot be read or parsed
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        match fs::read(path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// # Errors
+    /// Fails if the file cannot be written
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+}
+
+/// Outcome of a sync.
+#[derive(Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncSummary {
+    /// Games exported for the first time.
+    pub new: usize,
+    /// Games that were already exported.
+    pub skipped: usize,
+    /// Games that could not be fetched or that a sink rejected.
+    pub failed: usize,
+}
+
+/// Where and how [`HttpSink`] uploads games.
+#[derive(Debug, Clone)]
+pub struct HttpSinkConfig {
+    /// Games are posted to `{base_url}match`.
+    pub base_url: Url,
+    pub bearer_token: Option<String>,
+    pub timeout: Duration,
+    /// Number of games posted concurrently.
+    pub batch_size: usize,
+}
+
+impl Default for HttpSinkConfig {
+    fn default() -> Self {
+        Self {
+            base_url: Url::parse(DEFAULT_BASE_URL).expect("default base url is valid"),
+            bearer_token: None,
+            timeout: Duration::from_secs(30),
+            batch_size: 10,
+        }
+    }
+}
+
+impl HttpSinkConfig {
+    /// Default config uploading to `base_url` instead, whether or not it ends in a
+    /// slash.
+    ///
+    /// # Errors
+    /// Fails if `base_url` is not a valid url
+    pub fn with_base_url(base_url: &str) -> Result<Self, Error> {
+        Ok(Self {
+            base_url: parse_base_url(base_url)?,
+            ..Default::default()
+        })
+    }
+}
+
+/// Uploads games to a stats server, one `POST /match` per game. Responses with a
+/// non-success status count as failures.
+#[derive(Debug, Clone)]
+pub struct HttpSink {
+    config: HttpSinkConfig,
+    client: reqwest::Client,
+}
+
+impl HttpSink {
+    /// # Errors
+    /// Fails if the http client cannot be constructed
+    pub fn new(config: HttpSinkConfig) -> Result<Self, Error> {
+        let client = reqwest::ClientBuilder::new()
+            .timeout(config.timeout)
+            .build()?;
+        Ok(Self { config, client })
+    }
+}
+
+impl MatchSink for HttpSink {
+    fn name(&self) -> &str {
+        self.config.base_url.as_str()
+    }
+
+    fn send<'a>(
+        &'a mut self,
+        games: &'a [LolMatchHistoryMatchHistoryGame],
+    ) -> BoxFuture<'a, Vec<Result<(), Error>>> {
+        Box::pin(async move {
+            let url = match self.config.base_url.join("match") {
+                Ok(url) => url,
+                Err(e) => return games.iter().map(|_| Err(e.into())).collect(),
+            };
+            let mut results = Vec::with_capacity(games.len());
+            for batch in games.chunks(self.config.batch_size.max(1)) {
+                let requests = batch.iter().map(|game| {
+                    let mut request = self.client.post(url.clone()).json(game);
+                    if let Some(token) = &self.config.bearer_token {
+                        request = request.bearer_auth(token);
+                    }
+                    async move {
+                        request.send().await?.error_for_status()?;
+                        Ok(())
+                    }
+                });
+                results.extend(join_all(requests).await);
+            }
+            results
+        })
+    }
+}
+
+/// Appends games to a file with one json encoded game per line.
+#[derive(Debug)]
+pub struct JsonLinesSink {
+    name: String,
+    file: Arc<Mutex<File>>,
+}
+
+impl JsonLinesSink {
+    /// # Errors
+    /// Fails if `path` cannot be opened for appending
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            name: path.display().to_string(),
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+}
+
+impl MatchSink for JsonLinesSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send<'a>(
+        &'a mut self,
+        games: &'a [LolMatchHistoryMatchHistoryGame],
+    ) -> BoxFuture<'a, Vec<Result<(), Error>>> {
+        let lines = games
+            .iter()
+            .map(|game| {
+                let mut line = serde_json::to_vec(game)?;
+                line.push(b'\n');
+                Ok(line)
+            })
+            .collect();
+        Box::pin(append_blocking(self.file.clone(), lines))
+    }
+}
+
+/// Appends one row per participant to a csv file. Game, identity, participant and
+/// statistics fields are flattened into columns; the timeline is left out.
+#[derive(Debug)]
+pub struct CsvSink {
+    name: String,
+    columns: Vec<String>,
+    file: Arc<Mutex<File>>,
+}
+
+impl CsvSink {
+    /// Writes a header row first if the file is empty. The columns are the same for
+    /// every game.
+    ///
+    /// # Errors
+    /// Fails if `path` cannot be opened for appending
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        let columns = csv_columns()?;
+        if file.metadata()?.len() == 0 {
+            file.write_all(&encode_csv([&columns])?)?;
+        }
+        Ok(Self {
+            name: path.display().to_string(),
+            columns,
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+}
+
+impl MatchSink for CsvSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send<'a>(
+        &'a mut self,
+        games: &'a [LolMatchHistoryMatchHistoryGame],
+    ) -> BoxFuture<'a, Vec<Result<(), Error>>> {
+        let rows = games
+            .iter()
+            .map(|game| encode_csv(&csv_rows(game, &self.columns)?))
+            .collect();
+        Box::pin(append_blocking(self.file.clone(), rows))
+    }
+}
+
+/// Appends the encoded `records` of every game to `file` on the blocking thread pool,
+/// returning the outcome for every game. Each game is written at once and cut off
+/// again if that fails, so a failed game never leaves a partial record behind.
+async fn append_blocking(
+    file: Arc<Mutex<File>>,
+    records: Vec<Result<Vec<u8>, Error>>,
+) -> Vec<Result<(), Error>> {
+    spawn_blocking(move || {
+        let mut file = file.lock().unwrap();
+        records
+            .into_iter()
+            .map(|record| {
+                let record = record?;
+                let len = file.metadata()?.len();
+                if let Err(e) = file.write_all(&record) {
+                    file.set_len(len)?;
+                    return Err(e.into());
+                }
+                Ok(())
+            })
+            .collect()
+    })
+    .await
+}
+
+/// Csv encoded `rows`.
+fn encode_csv<R: AsRef<[u8]>>(
+    rows: impl IntoIterator<Item = impl IntoIterator<Item = R>>,
+) -> Result<Vec<u8>, Error> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(Vec::new());
+    for row in rows {
+        writer.write_record(row)?;
+    }
+    writer.into_inner().map_err(|e| Error::Io(e.into_error()))
+}
+
+/// Csv columns in the order [`participant_rows`] produces them.
+fn csv_columns() -> Result<Vec<String>, Error> {
+    let game = LolMatchHistoryMatchHistoryGame {
+        participants: vec![LolMatchHistoryMatchHistoryParticipant::default()],
+        ..Default::default()
+    };
+    Ok(participant_rows(&game)?
+        .swap_remove(0)
+        .into_iter()
+        .map(|(column, _)| column)
+        .collect())
+}
+
+/// Csv records for every participant in `game`, with the values in the order of
+/// `columns`.
+fn csv_rows(
+    game: &LolMatchHistoryMatchHistoryGame,
+    columns: &[String],
+) -> Result<Vec<Vec<String>>, Error> {
+    Ok(participant_rows(game)?
+        .into_iter()
+        .map(|row| {
+            let mut values: HashMap<_, _> = row.into_iter().collect();
+            columns
+                .iter()
+                .map(|column| values.remove(column).unwrap_or_default())
+                .collect()
+        })
+        .collect())
+}
+
+/// Flattened `(column, value)` pairs for every participant in `game`.
+fn participant_rows(
+    game: &LolMatchHistoryMatchHistoryGame,
+) -> Result<Vec<Vec<(String, String)>>, Error> {
+    let game_columns = [
+        ("gameId", game.game_id.to_string()),
+        ("platformId", game.platform_id.clone()),
+        ("gameCreation", game.game_creation.to_string()),
+        ("gameDuration", game.game_duration.to_string()),
+        ("queueId", game.queue_id.to_string()),
+        ("mapId", game.map_id.to_string()),
+        ("gameVersion", game.game_version.clone()),
+        ("gameMode", game.game_mode.clone()),
+        ("gameType", game.game_type.clone()),
+    ];
+    game.participants
+        .iter()
+        .map(|participant| {
+            let identity = game
+                .participant_identities
+                .iter()
+                .find(|x| x.participant_id == participant.participant_id)
+                .cloned()
+                .unwrap_or_else(|| LolMatchHistoryMatchHistoryParticipantIdentities {
+                    participant_id: participant.participant_id,
+                    ..Default::default()
+                });
+            let mut row: Vec<_> = game_columns
+                .iter()
+                .map(|(k, v)| ((*k).to_owned(), v.clone()))
+                .collect();
+            row.extend([
+                (
+                    "participantId".to_owned(),
+                    participant.participant_id.to_string(),
+                ),
+                ("teamId".to_owned(), participant.team_id.to_string()),
+                ("championId".to_owned(), participant.champion_id.to_string()),
+                ("spell1Id".to_owned(), participant.spell1_id.to_string()),
+                ("spell2Id".to_owned(), participant.spell2_id.to_string()),
+            ]);
+            flatten_into(&mut row, "player.", &identity.player)?;
+            flatten_into(&mut row, "stats.", &participant.stats)?;
+            Ok(row)
+        })
+        .collect()
+}
+
+fn flatten_into(
+    row: &mut Vec<(String, String)>,
+    prefix: &str,
+    value: &impl Serialize,
+) -> Result<(), Error> {
+    if let Value::Object(fields) = serde_json::to_value(value)? {
+        row.extend(fields.into_iter().map(|(k, v)| {
+            let v = match v {
+                Value::String(s) => s,
+                v => v.to_string(),
+            };
+            (format!("{prefix}{k}"), v)
+        }));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CsvSink, HttpSink, HttpSinkConfig, JsonLinesSink, MatchSink, SyncState};
+    use crate::{
+        test_utils::{serve_recording, temp_dir},
+        types::{
+            LolMatchHistoryMatchHistoryGame, LolMatchHistoryMatchHistoryParticipant,
+            LolMatchHistoryMatchHistoryParticipantIdentities,
+        },
+        Error,
+    };
+    use std::{fs, time::Duration};
+
+    pub(super) fn game(game_id: u64) -> LolMatchHistoryMatchHistoryGame {
+        let mut game = LolMatchHistoryMatchHistoryGame {
+            game_id,
+            game_type: "CUSTOM_GAME".to_owned(),
+            ..Default::default()
+        };
+        for participant_id in 1..=10 {
+            let mut participant = LolMatchHistoryMatchHistoryParticipant {
+                participant_id,
+                team_id: if participant_id <= 5 { 100 } else { 200 },
+                champion_id: i32::try_from(participant_id).unwrap(),
+                ..Default::default()
+            };
+            participant.stats.kills = participant_id.into();
+            participant.stats.win = participant_id <= 5;
+            game.participants.push(participant);
+            let mut identity = LolMatchHistoryMatchHistoryParticipantIdentities {
+                participant_id,
+                ..Default::default()
+            };
+            identity.player.summoner_id = 1000 + u64::from(participant_id);
+            identity.player.summoner_name = format!("Player {participant_id}");
+            game.participant_identities.push(identity);
+        }
+        game
+    }
+
+    #[tokio::test]
+    async fn uploads_to_configured_server() -> Result<(), Error> {
+        let (base_url, requests) = serve_recording(vec![("/stats/match", String::new())]);
+        let mut sink = HttpSink::new(HttpSinkConfig {
+            bearer_token: Some("secret".to_owned()),
+            timeout: Duration::from_secs(5),
+            batch_size: 2,
+            ..HttpSinkConfig::with_base_url(&format!("{base_url}stats/"))?
+        })?;
+        let games: Vec<_> = (1..=3).map(game).collect();
+
+        let results = sink.send(&games).await;
+        assert!(results.iter().all(Result::is_ok));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests.iter().all(|r| r.method == "POST"
+            && r.path == "/stats/match"
+            && r.headers
+                .contains(&"authorization: Bearer secret".to_owned())));
+        assert!(requests.iter().any(|r| r.body.contains(r#""gameId":2"#)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn base_url_without_trailing_slash() -> Result<(), Error> {
+        let (base_url, requests) = serve_recording(vec![("/stats/match", String::new())]);
+        let mut sink = HttpSink::new(HttpSinkConfig::with_base_url(&format!("{base_url}stats"))?)?;
+
+        let results = sink.send(&[game(1)]).await;
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(requests.lock().unwrap()[0].path, "/stats/match");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rejected_uploads_fail() -> Result<(), Error> {
+        let (base_url, _) = serve_recording(vec![]);
+        let mut sink = HttpSink::new(HttpSinkConfig::with_base_url(&base_url)?)?;
+        let results = sink.send(&[game(1)]).await;
+        assert!(results[0].is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn sync_state_round_trip() -> Result<(), Error> {
+        let dir = temp_dir("sync-state");
+        fs::create_dir_all(&dir)?;
+        let path = dir.join("state.json");
+        assert_eq!(SyncState::load(&path)?, SyncState::default());
+
+        let state = SyncState {
+            exported: [1, 2].into(),
+            failed: [(3, [0, 2].into())].into(),
+            newest_game_creation: 1_700_000_000_000,
+            resume_before: Some(1_600_000_000_000),
+            pending_newest_game_creation: 1_800_000_000_000,
+        };
+        state.save(&path)?;
+        assert_eq!(SyncState::load(&path)?, state);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn file_sinks_append() -> Result<(), Error> {
+        let dir = temp_dir("file-sinks");
+        fs::create_dir_all(&dir)?;
+        let json_path = dir.join("games.jsonl");
+        let csv_path = dir.join("participants.csv");
+
+        for game_id in [1, 2] {
+            let mut sinks: Vec<Box<dyn MatchSink>> = vec![
+                Box::new(JsonLinesSink::new(&json_path)?),
+                Box::new(CsvSink::new(&csv_path)?),
+            ];
+            for sink in &mut sinks {
+                assert!(sink.send(&[game(game_id)]).await.iter().all(Result::is_ok));
+            }
+        }
+
+        let json = fs::read_to_string(&json_path)?;
+        let games: Vec<LolMatchHistoryMatchHistoryGame> = json
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        assert_eq!(games.iter().map(|g| g.game_id).collect::<Vec<_>>(), [1, 2]);
+
+        let csv = fs::read_to_string(&csv_path)?;
+        let mut lines = csv.lines();
+        let header: Vec<_> = lines.next().unwrap().split(',').collect();
+        assert_eq!(header[0], "gameId");
+        assert!(header.contains(&"player.summonerName"));
+        assert!(header.contains(&"stats.kills"));
+        assert_eq!(lines.count(), 20);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn csv_header_is_fixed() -> Result<(), Error> {
+        let dir = temp_dir("csv-header");
+        fs::create_dir_all(&dir)?;
+        let csv_path = dir.join("participants.csv");
+
+        let mut sink = CsvSink::new(&csv_path)?;
+        let header = fs::read_to_string(&csv_path)?;
+        assert!(header.starts_with("gameId,"));
+
+        assert!(sink.send(&[game(7)]).await.iter().all(Result::is_ok));
+        let mut reader = csv::Reader::from_path(&csv_path)?;
+        let kills = reader.headers()?.iter().position(|x| x == "stats.kills");
+        let row = reader.records().nth(2).unwrap()?;
+        assert_eq!(&row[0], "7");
+        assert_eq!(&row[kills.unwrap()], "3");
+        Ok(())
+    }
+}
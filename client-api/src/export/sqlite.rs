@@ -0,0 +1,329 @@
+use super::MatchSink;
+use crate::{
+    types::{LolMatchHistoryMatchHistoryGame, LolMatchHistoryMatchHistoryParticipantStatistics},
+    Error,
+};
+use async_std::task::spawn_blocking;
+use futures::future::BoxFuture;
+use rusqlite::{params, params_from_iter, types::Value as SqlValue, Connection, Transaction};
+use serde_json::Value;
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS games (
+    game_id INTEGER PRIMARY KEY,
+    platform_id TEXT NOT NULL,
+    game_creation INTEGER NOT NULL,
+    game_creation_date TEXT NOT NULL,
+    game_duration INTEGER NOT NULL,
+    queue_id INTEGER NOT NULL,
+    map_id INTEGER NOT NULL,
+    season_id INTEGER,
+    game_version TEXT NOT NULL,
+    game_mode TEXT NOT NULL,
+    game_type TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS teams (
+    game_id INTEGER NOT NULL REFERENCES games ON DELETE CASCADE,
+    team_id INTEGER NOT NULL,
+    win INTEGER NOT NULL,
+    first_blood INTEGER NOT NULL,
+    first_tower INTEGER NOT NULL,
+    first_inhibitor INTEGER NOT NULL,
+    first_baron INTEGER NOT NULL,
+    first_dragon INTEGER NOT NULL,
+    tower_kills INTEGER NOT NULL,
+    inhibitor_kills INTEGER NOT NULL,
+    baron_kills INTEGER NOT NULL,
+    dragon_kills INTEGER NOT NULL,
+    rift_herald_kills INTEGER NOT NULL,
+    PRIMARY KEY (game_id, team_id)
+);
+CREATE TABLE IF NOT EXISTS bans (
+    game_id INTEGER NOT NULL REFERENCES games ON DELETE CASCADE,
+    team_id INTEGER NOT NULL,
+    pick_turn INTEGER NOT NULL,
+    champion_id INTEGER NOT NULL,
+    PRIMARY KEY (game_id, team_id, pick_turn)
+);
+CREATE TABLE IF NOT EXISTS participants (
+    game_id INTEGER NOT NULL REFERENCES games ON DELETE CASCADE,
+    participant_id INTEGER NOT NULL,
+    team_id INTEGER NOT NULL,
+    champion_id INTEGER NOT NULL,
+    spell1_id INTEGER NOT NULL,
+    spell2_id INTEGER NOT NULL,
+    summoner_id INTEGER NOT NULL,
+    account_id INTEGER NOT NULL,
+    summoner_name TEXT NOT NULL,
+    role TEXT NOT NULL,
+    lane TEXT NOT NULL,
+    PRIMARY KEY (game_id, participant_id)
+);
+";
+
+/// Writes games into a normalised sqlite database with `games`, `teams`, `bans`,
+/// `participants` and `stats` tables. Games that already exist are replaced.
+#[derive(Debug)]
+pub struct SqliteSink {
+    name: String,
+    connection: Arc<Mutex<Connection>>,
+    stat_columns: Arc<[String]>,
+}
+
+impl SqliteSink {
+    /// Opens or creates the database at `path` and creates any missing tables.
+    ///
+    /// # Errors
+    /// Fails if the database cannot be opened or the tables cannot be created
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        Self::from_connection(Connection::open(path)?, path.display().to_string())
+    }
+
+    fn from_connection(connection: Connection, name: String) -> Result<Self, Error> {
+        // The stats table has one column per statistic, named like the rust fields
+        let Value::Object(stats) =
+            serde_json::to_value(LolMatchHistoryMatchHistoryParticipantStatistics::default())?
+        else {
+            unreachable!("statistics serialize to an object");
+        };
+        let stat_columns: Vec<_> = stats
+            .keys()
+            .filter(|k| *k != "participantId")
+            .map(|k| snake_case(k))
+            .collect();
+
+        connection.execute_batch("PRAGMA foreign_keys = ON;")?;
+        connection.execute_batch(SCHEMA)?;
+        connection.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS stats (
+                game_id INTEGER NOT NULL,
+                participant_id INTEGER NOT NULL,
+                {},
+                PRIMARY KEY (game_id, participant_id),
+                FOREIGN KEY (game_id, participant_id)
+                    REFERENCES participants (game_id, participant_id) ON DELETE CASCADE
+            );",
+            stat_columns
+                .iter()
+                .map(|c| format!("{c} INTEGER NOT NULL"))
+                .collect::<Vec<_>>()
+                .join(",\n")
+        ))?;
+        Ok(Self {
+            name,
+            connection: Arc::new(Mutex::new(connection)),
+            stat_columns: stat_columns.into(),
+        })
+    }
+}
+
+fn write_game(
+    connection: &mut Connection,
+    stat_columns: &[String],
+    game: &LolMatchHistoryMatchHistoryGame,
+) -> Result<(), Error> {
+    let transaction = connection.transaction()?;
+    insert_game(&transaction, stat_columns, game)?;
+    transaction.commit()?;
+    Ok(())
+}
+
+fn insert_game(
+    transaction: &Transaction,
+    stat_columns: &[String],
+    game: &LolMatchHistoryMatchHistoryGame,
+) -> Result<(), Error> {
+    transaction.execute("DELETE FROM games WHERE game_id = ?1", [game.game_id])?;
+    transaction.execute(
+        "INSERT INTO games VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            game.game_id,
+            game.platform_id,
+            game.game_creation,
+            game.game_creation_date,
+            game.game_duration,
+            game.queue_id,
+            game.map_id,
+            game.season_id,
+            game.game_version,
+            game.game_mode,
+            game.game_type,
+        ],
+    )?;
+
+    for team in &game.teams {
+        transaction.execute(
+            "INSERT INTO teams VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            params![
+                game.game_id,
+                team.team_id,
+                team.win == "Win",
+                team.first_blood,
+                team.first_tower,
+                team.first_inhibitor,
+                team.first_baron,
+                team.first_dargon,
+                team.tower_kills,
+                team.inhibitor_kills,
+                team.baron_kills,
+                team.dragon_kills,
+                team.rift_herald_kills,
+            ],
+        )?;
+        for ban in &team.bans {
+            transaction.execute(
+                "INSERT INTO bans VALUES (?1, ?2, ?3, ?4)",
+                params![game.game_id, team.team_id, ban.pick_turn, ban.champion_id],
+            )?;
+        }
+    }
+
+    let insert_stats = format!(
+        "INSERT INTO stats (game_id, participant_id, {}) VALUES ({})",
+        stat_columns.join(", "),
+        vec!["?"; stat_columns.len() + 2].join(", ")
+    );
+    for participant in &game.participants {
+        let player = game
+            .participant_identities
+            .iter()
+            .find(|x| x.participant_id == participant.participant_id)
+            .map(|x| x.player.clone())
+            .unwrap_or_default();
+        transaction.execute(
+            "INSERT INTO participants VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                game.game_id,
+                participant.participant_id,
+                participant.team_id,
+                participant.champion_id,
+                participant.spell1_id,
+                participant.spell2_id,
+                player.summoner_id,
+                player.account_id,
+                player.summoner_name,
+                participant.timeline.role,
+                participant.timeline.lane,
+            ],
+        )?;
+
+        let Value::Object(stats) = serde_json::to_value(&participant.stats)? else {
+            unreachable!("statistics serialize to an object");
+        };
+        let values =
+            stats
+                .into_iter()
+                .filter(|(k, _)| k != "participantId")
+                .map(|(_, v)| match v {
+                    Value::Bool(b) => SqlValue::Integer(b.into()),
+                    v => SqlValue::Integer(v.as_i64().unwrap_or_default()),
+                });
+        transaction.execute(
+            &insert_stats,
+            params_from_iter(
+                [
+                    SqlValue::Integer(i64::try_from(game.game_id).unwrap_or_default()),
+                    SqlValue::Integer(participant.participant_id.into()),
+                ]
+                .into_iter()
+                .chain(values),
+            ),
+        )?;
+    }
+    Ok(())
+}
+
+impl MatchSink for SqliteSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send<'a>(
+        &'a mut self,
+        games: &'a [LolMatchHistoryMatchHistoryGame],
+    ) -> BoxFuture<'a, Vec<Result<(), Error>>> {
+        let games = games.to_vec();
+        let connection = self.connection.clone();
+        let stat_columns = self.stat_columns.clone();
+        Box::pin(spawn_blocking(move || {
+            let mut connection = connection.lock().unwrap();
+            games
+                .iter()
+                .map(|game| write_game(&mut connection, &stat_columns, game))
+                .collect()
+        }))
+    }
+}
+
+/// Converts a camel case field name to snake case, e.g. `timeCCingOthers` to
+/// `time_c_cing_others`.
+fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for c in name.chars() {
+        if c.is_uppercase() {
+            out.push('_');
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{snake_case, SqliteSink};
+    use crate::{
+        export::{tests::game, MatchSink},
+        types::LolMatchHistoryMatchHistoryTeam,
+        Error,
+    };
+    use rusqlite::Connection;
+
+    #[test]
+    fn stat_column_names() {
+        assert_eq!(snake_case("timeCCingOthers"), "time_c_cing_others");
+        assert_eq!(snake_case("perk0Var1"), "perk0_var1");
+        assert_eq!(snake_case("item0"), "item0");
+    }
+
+    #[tokio::test]
+    async fn writes_normalised_tables() -> Result<(), Error> {
+        let mut sink = SqliteSink::from_connection(Connection::open_in_memory()?, String::new())?;
+        let mut game = game(1);
+        game.teams = vec![LolMatchHistoryMatchHistoryTeam {
+            team_id: 100,
+            win: "Win".to_owned(),
+            ..Default::default()
+        }];
+        game.teams[0].bans.push(Default::default());
+
+        // Sending the same game twice replaces it
+        assert!(sink.send(&[game.clone()]).await.iter().all(Result::is_ok));
+        assert!(sink.send(&[game]).await.iter().all(Result::is_ok));
+
+        let count = |table: &str| -> Result<i64, Error> {
+            Ok(sink.connection.lock().unwrap().query_row(
+                &format!("SELECT COUNT(*) FROM {table}"),
+                [],
+                |row| row.get(0),
+            )?)
+        };
+        assert_eq!(count("games")?, 1);
+        assert_eq!(count("teams")?, 1);
+        assert_eq!(count("bans")?, 1);
+        assert_eq!(count("participants")?, 10);
+        assert_eq!(count("stats")?, 10);
+
+        let kills: i64 = sink.connection.lock().unwrap().query_row(
+            "SELECT SUM(kills) FROM stats WHERE win = 1",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(kills, 15);
+        Ok(())
+    }
+}
@@ -4,12 +4,24 @@ use types::ApiError;
 
 #[cfg(feature = "actions")]
 pub mod actions;
+#[cfg(feature = "assets")]
+pub mod assets;
 #[cfg(feature = "client")]
 pub mod client;
 #[cfg(feature = "ddragon")]
 pub mod ddragon;
 #[cfg(feature = "endpoints")]
 pub mod endpoints;
+#[cfg(feature = "export")]
+pub mod export;
+#[cfg(feature = "scoreboard")]
+pub mod scoreboard;
+#[cfg(feature = "stats")]
+pub mod stats;
+// Not every helper is used by every feature combination
+#[cfg(test)]
+#[allow(dead_code)]
+mod test_utils;
 #[cfg(feature = "types")]
 pub mod types;
 
@@ -49,7 +61,27 @@ pub enum Error {
     WrongGameMode,
     #[error("Could not move player to given location")]
     PlayerMove,
-    #[error("Custom error")]
+    #[error("No champion matches {0}")]
+    ChampionNotFound(String),
+    #[error("Not enough champions match the pool filters")]
+    NotEnoughChampions,
+    #[error("Too many players to balance: {0}")]
+    TooManyPlayers(usize),
+    #[error("Io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[cfg(any(feature = "export", feature = "stats"))]
+    #[error("Csv export failed: {0}")]
+    Csv(#[from] csv::Error),
+    #[cfg(feature = "sqlite")]
+    #[error("Sqlite export failed: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("{0} is not cached and offline mode is enabled")]
+    NotCached(String),
+    #[error("No asset found for {0}")]
+    AssetNotFound(String),
+    #[error("{0} is not a relative asset path")]
+    InvalidAssetPath(String),
+    #[error("{0}")]
     Custom(String),
 }
 
@@ -0,0 +1,439 @@
+use crate::{
+    ddragon::{index::ChampionIndex, types::Item},
+    types::{ItemId, LolMatchHistoryMatchHistoryGame},
+};
+use itertools::Itertools;
+use std::fmt::Write;
+
+const HEADER: [&str; 7] = [
+    "Summoner", "Champion", "K/D/A", "CS", "Damage", "Gold", "Items",
+];
+/// Columns that hold numbers and are aligned to the right.
+const NUMERIC: [bool; 7] = [false, false, true, true, true, true, false];
+
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_BOLD: &str = "\x1b[1m";
+const ANSI_GREEN: &str = "\x1b[32m";
+const ANSI_RED: &str = "\x1b[31m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScoreboardFormat {
+    /// Aligned columns for monospaced text.
+    #[default]
+    Plain,
+    /// One Markdown table per team, e.g. for Discord.
+    Markdown,
+    /// Aligned columns with ANSI colors for terminals.
+    Ansi,
+}
+
+/// Results of a finished game with champions, items and bans resolved to names.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scoreboard {
+    pub game_id: u64,
+    /// Game length in seconds.
+    pub duration: u32,
+    pub teams: Vec<TeamScore>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeamScore {
+    pub team_id: u32,
+    pub win: bool,
+    pub bans: Vec<String>,
+    pub players: Vec<PlayerScore>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerScore {
+    pub summoner_name: String,
+    pub champion: String,
+    pub kills: i64,
+    pub deaths: i64,
+    pub assists: i64,
+    /// Lane minions and jungle monsters killed.
+    pub cs: i64,
+    /// Damage dealt to champions.
+    pub damage: i64,
+    pub gold: i64,
+    pub items: Vec<String>,
+}
+
+impl PlayerScore {
+    fn row(&self) -> [String; 7] {
+        [
+            self.summoner_name.clone(),
+            self.champion.clone(),
+            format!("{}/{}/{}", self.kills, self.deaths, self.assists),
+            self.cs.to_string(),
+            self.damage.to_string(),
+            self.gold.to_string(),
+            self.items.join(", "),
+        ]
+    }
+}
+
+impl Scoreboard {
+    /// Builds the scoreboard of `game`. Champion names are looked up in `champions` and
+    /// item names in `items`; anything that cannot be found is shown by its id.
+    #[must_use]
+    pub fn new(
+        game: &LolMatchHistoryMatchHistoryGame,
+        champions: &ChampionIndex,
+        items: Option<&Item>,
+    ) -> Self {
+        let item_name = |id: ItemId| {
+            items
+                .and_then(|items| items.get(id.0))
+                .map_or_else(|| id.to_string(), |item| item.name.clone())
+        };
+
+        let team_ids = game
+            .teams
+            .iter()
+            .map(|team| team.team_id)
+            .chain(game.participants.iter().map(|x| x.team_id))
+            .unique()
+            .sorted();
+        let teams = team_ids
+            .map(|team_id| {
+                let team = game.teams.iter().find(|team| team.team_id == team_id);
+                let players: Vec<_> = game
+                    .participants
+                    .iter()
+                    .filter(|x| x.team_id == team_id)
+                    .map(|participant| {
+                        let stats = &participant.stats;
+                        PlayerScore {
+                            summoner_name: game
+                                .participant_identities
+                                .iter()
+                                .find(|x| x.participant_id == participant.participant_id)
+                                .map(|x| x.player.summoner_name.clone())
+                                .unwrap_or_default(),
+                            champion: champions.name(participant.champion_id),
+                            kills: stats.kills,
+                            deaths: stats.deaths,
+                            assists: stats.assists,
+                            cs: stats.cs(),
+                            damage: stats.total_damage_dealt_to_champions,
+                            gold: stats.gold_earned,
+                            items: stats
+                                .items()
+                                .into_iter()
+                                .filter(|id| !id.is_empty())
+                                .map(item_name)
+                                .collect(),
+                        }
+                    })
+                    .collect();
+                TeamScore {
+                    team_id,
+                    win: team.is_some_and(|team| team.win == "Win")
+                        || game
+                            .participants
+                            .iter()
+                            .any(|x| x.team_id == team_id && x.stats.win),
+                    bans: team
+                        .map(|team| {
+                            team.bans
+                                .iter()
+                                .filter(|ban| ban.champion_id > 0)
+                                .map(|ban| champions.name(ban.champion_id))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                    players,
+                }
+            })
+            .collect();
+
+        Self {
+            game_id: game.game_id,
+            duration: game.game_duration,
+            teams,
+        }
+    }
+
+    #[must_use]
+    pub fn render(&self, format: ScoreboardFormat) -> String {
+        match format {
+            ScoreboardFormat::Plain => self.columns(false),
+            ScoreboardFormat::Markdown => self.markdown(),
+            ScoreboardFormat::Ansi => self.columns(true),
+        }
+    }
+
+    /// Short summary without items, damage and gold that fits in the lobby chat.
+    #[must_use]
+    pub fn compact(&self) -> String {
+        #[allow(unstable_name_collisions)]
+        std::iter::once(".\n".to_owned())
+            .chain(
+                self.teams
+                    .iter()
+                    .enumerate()
+                    .map(|(i, team)| {
+                        let mut out = format!(
+                            "Team {} {} - Bans: {}",
+                            i + 1,
+                            if team.win { "won" } else { "lost" },
+                            bans(team)
+                        );
+                        for player in &team.players {
+                            let _ = write!(
+                                out,
+                                "\n{} ({}) {}/{}/{} {}cs",
+                                player.summoner_name,
+                                player.champion,
+                                player.kills,
+                                player.deaths,
+                                player.assists,
+                                player.cs
+                            );
+                        }
+                        out
+                    })
+                    .intersperse("\n----------\n".into()),
+            )
+            .collect()
+    }
+
+    fn title(&self) -> String {
+        format!(
+            "Game {} - {}:{:02}",
+            self.game_id,
+            self.duration / 60,
+            self.duration % 60
+        )
+    }
+
+    fn columns(&self, ansi: bool) -> String {
+        let rows: Vec<Vec<[String; 7]>> = self
+            .teams
+            .iter()
+            .map(|team| team.players.iter().map(PlayerScore::row).collect())
+            .collect();
+        let mut widths = HEADER.map(|x| x.chars().count());
+        for row in rows.iter().flatten() {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let line = |cells: &[String]| {
+            cells
+                .iter()
+                .zip(widths)
+                .zip(NUMERIC)
+                .map(|((cell, width), numeric)| {
+                    if numeric {
+                        format!("{cell:>width$}")
+                    } else {
+                        format!("{cell:<width$}")
+                    }
+                })
+                .join("  ")
+                .trim_end()
+                .to_owned()
+        };
+        let paint = |text: String, color: &str| {
+            if ansi {
+                format!("{color}{text}{ANSI_RESET}")
+            } else {
+                text
+            }
+        };
+
+        let mut out = paint(self.title(), ANSI_BOLD);
+        for (i, (team, rows)) in self.teams.iter().zip(&rows).enumerate() {
+            let result = if team.win { "Victory" } else { "Defeat" };
+            let color = if team.win { ANSI_GREEN } else { ANSI_RED };
+            let _ = write!(
+                out,
+                "\n\n{}\nBans: {}\n{}",
+                paint(format!("Team {} - {result}", i + 1), color),
+                bans(team),
+                paint(line(&HEADER.map(str::to_owned)), ANSI_BOLD)
+            );
+            for row in rows {
+                let _ = write!(out, "\n{}", line(row));
+            }
+        }
+        out
+    }
+
+    fn markdown(&self) -> String {
+        let escape = |cell: &str| cell.replace('|', "\\|");
+        let row = |cells: &[String]| format!("| {} |", cells.iter().map(|x| escape(x)).join(" | "));
+
+        let mut out = format!("**{}**", self.title());
+        for (i, team) in self.teams.iter().enumerate() {
+            let _ = write!(
+                out,
+                "\n\n**Team {} - {}**\nBans: {}\n\n{}\n| {} |",
+                i + 1,
+                if team.win { "Victory" } else { "Defeat" },
+                escape(&bans(team)),
+                row(&HEADER.map(str::to_owned)),
+                NUMERIC
+                    .iter()
+                    .map(|&numeric| if numeric { "---:" } else { "---" })
+                    .join(" | ")
+            );
+            for player in &team.players {
+                let _ = write!(out, "\n{}", row(&player.row()));
+            }
+        }
+        out
+    }
+}
+
+fn bans(team: &TeamScore) -> String {
+    if team.bans.is_empty() {
+        "-".to_owned()
+    } else {
+        team.bans.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Scoreboard, ScoreboardFormat};
+    use crate::{
+        ddragon::{
+            index::ChampionIndex,
+            types::{ChampionData, Item, ItemData},
+        },
+        types::{
+            LolMatchHistoryMatchHistoryGame, LolMatchHistoryMatchHistoryParticipant,
+            LolMatchHistoryMatchHistoryParticipantIdentities, LolMatchHistoryMatchHistoryTeam,
+            LolMatchHistoryMatchHistoryTeamBan,
+        },
+    };
+
+    fn scoreboard() -> Scoreboard {
+        let mut game = LolMatchHistoryMatchHistoryGame {
+            game_id: 7,
+            game_duration: 1805,
+            ..Default::default()
+        };
+        for (participant_id, champion_id, name) in [(1, 4, "Card|Master"), (2, 62, "B")] {
+            let team_id = participant_id * 100;
+            let mut participant = LolMatchHistoryMatchHistoryParticipant {
+                participant_id,
+                team_id,
+                champion_id,
+                ..Default::default()
+            };
+            participant.stats.win = participant_id == 1;
+            participant.stats.kills = 12;
+            participant.stats.deaths = 3;
+            participant.stats.assists = 4;
+            participant.stats.total_minions_killed = 150;
+            participant.stats.neutral_minions_killed = 8;
+            participant.stats.total_damage_dealt_to_champions = 21000;
+            participant.stats.gold_earned = 11000;
+            participant.stats.item0 = 3089;
+            participant.stats.item6 = 3340;
+            game.participants.push(participant);
+
+            let mut identity = LolMatchHistoryMatchHistoryParticipantIdentities {
+                participant_id,
+                ..Default::default()
+            };
+            identity.player.summoner_name = name.to_owned();
+            game.participant_identities.push(identity);
+
+            game.teams.push(LolMatchHistoryMatchHistoryTeam {
+                team_id,
+                win: if participant_id == 1 { "Win" } else { "Fail" }.to_owned(),
+                bans: vec![LolMatchHistoryMatchHistoryTeamBan {
+                    champion_id: if participant_id == 1 { 62 } else { -1 },
+                    pick_turn: 1,
+                }],
+                ..Default::default()
+            });
+        }
+
+        let champions = ChampionIndex::new([
+            ChampionData {
+                key: "4".to_owned(),
+                name: "Twisted Fate".to_owned(),
+                ..Default::default()
+            },
+            ChampionData {
+                key: "62".to_owned(),
+                name: "Wukong".to_owned(),
+                ..Default::default()
+            },
+        ]);
+        let mut items = Item::default();
+        items.data.insert(
+            "3089".to_owned(),
+            ItemData {
+                name: "Rabadon's Deathcap".to_owned(),
+                ..Default::default()
+            },
+        );
+        Scoreboard::new(&game, &champions, Some(&items))
+    }
+
+    #[test]
+    fn resolves_names() {
+        let scoreboard = scoreboard();
+        assert_eq!(scoreboard.teams.len(), 2);
+        let winners = &scoreboard.teams[0];
+        assert!(winners.win);
+        assert_eq!(winners.bans, ["Wukong"]);
+        assert_eq!(winners.players[0].champion, "Twisted Fate");
+        assert_eq!(winners.players[0].cs, 158);
+        assert_eq!(winners.players[0].items, ["Rabadon's Deathcap", "3340"]);
+        assert!(!scoreboard.teams[1].win);
+        assert!(scoreboard.teams[1].bans.is_empty());
+    }
+
+    #[test]
+    fn plain_and_ansi() {
+        let scoreboard = scoreboard();
+        let plain = scoreboard.render(ScoreboardFormat::Plain);
+        let lines: Vec<_> = plain.lines().collect();
+        assert_eq!(lines[0], "Game 7 - 30:05");
+        assert_eq!(lines[2], "Team 1 - Victory");
+        assert_eq!(lines[3], "Bans: Wukong");
+        assert_eq!(
+            lines[4],
+            "Summoner     Champion       K/D/A   CS  Damage   Gold  Items"
+        );
+        assert_eq!(
+            lines[5],
+            "Card|Master  Twisted Fate  12/3/4  158   21000  11000  Rabadon's Deathcap, 3340"
+        );
+        assert_eq!(lines[8], "Bans: -");
+        assert!(!plain.contains('\x1b'));
+
+        let ansi = scoreboard.render(ScoreboardFormat::Ansi);
+        assert!(ansi.contains("\x1b[32mTeam 1 - Victory\x1b[0m"));
+        assert!(ansi.contains("\x1b[31mTeam 2 - Defeat\x1b[0m"));
+        assert!(ansi.contains(lines[5]));
+    }
+
+    #[test]
+    fn markdown() {
+        let markdown = scoreboard().render(ScoreboardFormat::Markdown);
+        assert!(markdown.starts_with("**Game 7 - 30:05**\n\n**Team 1 - Victory**\nBans: Wukong\n"));
+        assert!(markdown.contains(
+            "| Summoner | Champion | K/D/A | CS | Damage | Gold | Items |\n\
+             | --- | --- | ---: | ---: | ---: | ---: | --- |\n\
+             | Card\\|Master | Twisted Fate | 12/3/4 | 158 | 21000 | 11000 | Rabadon's Deathcap, 3340 |"
+        ));
+    }
+
+    #[test]
+    fn compact() {
+        assert_eq!(
+            scoreboard().compact(),
+            ".\nTeam 1 won - Bans: Wukong\nCard|Master (Twisted Fate) 12/3/4 158cs\
+             \n----------\nTeam 2 lost - Bans: -\nB (Wukong) 12/3/4 158cs"
+        );
+    }
+}
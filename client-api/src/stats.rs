@@ -0,0 +1,747 @@
+use crate::{
+    types::{
+        kda_ratio, LolMatchHistoryMatchHistoryGame, LolMatchHistoryMatchHistoryParticipant,
+        LolMatchHistoryMatchHistoryParticipantIdentityPlayer,
+    },
+    Error,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Display,
+    fs,
+    path::Path,
+    str::FromStr,
+};
+
+pub mod awards;
+pub mod champions;
+pub mod lanes;
+pub mod pairs;
+pub mod rating;
+
+/// Games the stats are computed from, keyed by game id.
+#[derive(Debug, Clone, Default)]
+pub struct MatchStore {
+    games: BTreeMap<u64, LolMatchHistoryMatchHistoryGame>,
+}
+
+impl FromIterator<LolMatchHistoryMatchHistoryGame> for MatchStore {
+    fn from_iter<T: IntoIterator<Item = LolMatchHistoryMatchHistoryGame>>(iter: T) -> Self {
+        let mut store = Self::default();
+        store.extend(iter);
+        store
+    }
+}
+
+impl Extend<LolMatchHistoryMatchHistoryGame> for MatchStore {
+    fn extend<T: IntoIterator<Item = LolMatchHistoryMatchHistoryGame>>(&mut self, iter: T) {
+        for game in iter {
+            self.insert(game);
+        }
+    }
+}
+
+impl MatchStore {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads games written by [`crate::export::JsonLinesSink`], one game per line.
+    /// Later lines replace earlier lines for the same game.
+    ///
+    /// # Errors
+    /// Fails if the file cannot be read or a line is not a game.
+    pub fn load_json_lines(path: impl AsRef<Path>) -> Result<Self, Error> {
+        fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(Error::from))
+            .collect()
+    }
+
+    /// Adds `game`, replacing any game with the same id. Returns whether it was new.
+    pub fn insert(&mut self, game: LolMatchHistoryMatchHistoryGame) -> bool {
+        self.games.insert(game.game_id, game).is_none()
+    }
+
+    #[must_use]
+    pub fn get(&self, game_id: u64) -> Option<&LolMatchHistoryMatchHistoryGame> {
+        self.games.get(&game_id)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+
+    /// Every game, oldest first.
+    pub fn games(&self) -> impl DoubleEndedIterator<Item = &LolMatchHistoryMatchHistoryGame> {
+        let mut games: Vec<_> = self.games.values().collect();
+        games.sort_by_key(|game| (game.game_creation, game.game_id));
+        games.into_iter()
+    }
+
+    /// Every summoner with the number of games played, winrate and number of distinct
+    /// champions played. Only summoners with at least `cutoff` games are included.
+    #[must_use]
+    pub fn summoner_names(
+        &self,
+        order_by: SummonerOrder,
+        cutoff: u32,
+        order: Order,
+    ) -> Vec<SummonerSummary> {
+        let mut summoners: Vec<_> = self
+            .summoner_totals()
+            .into_values()
+            .filter(|x| x.games >= cutoff)
+            .map(|x| SummonerSummary {
+                summoner_id: x.summoner_id,
+                summoner_name: x.summoner_name,
+                games: x.games,
+                winrate: ratio(x.wins, x.games),
+                champs: u32::try_from(x.champions.len()).unwrap_or(u32::MAX),
+            })
+            .collect();
+        summoners.sort_by(|a, b| {
+            let ordering = match order_by {
+                SummonerOrder::Games => a.games.cmp(&b.games),
+                SummonerOrder::Winrate => a.winrate.total_cmp(&b.winrate),
+                SummonerOrder::ChampionsPlayed => a.champs.cmp(&b.champs),
+            };
+            order
+                .apply(ordering.then_with(|| a.games.cmp(&b.games)))
+                .then_with(|| a.summoner_id.cmp(&b.summoner_id))
+        });
+        summoners
+    }
+
+    /// The summary of a single summoner, as in [`MatchStore::summoner_names`].
+    #[must_use]
+    pub fn summoner(&self, summoner_id: u64) -> Option<SummonerSummary> {
+        self.summoner_names(SummonerOrder::Games, 0, Order::Descending)
+            .into_iter()
+            .find(|x| x.summoner_id == summoner_id)
+    }
+
+    /// Total kills, deaths and assists for every summoner with at least `cutoff` games,
+    /// ordered by KDA.
+    #[must_use]
+    pub fn kdas(&self, cutoff: u32, order: Order) -> Vec<Kda> {
+        let mut kdas: Vec<_> = self
+            .summoner_totals()
+            .into_values()
+            .filter(|x| x.games >= cutoff)
+            .map(|x| Kda {
+                summoner_id: x.summoner_id,
+                summoner_name: x.summoner_name,
+                games: x.games,
+                kills: x.kills,
+                deaths: x.deaths,
+                assists: x.assists,
+                kda: kda(x.kills, x.deaths, x.assists),
+            })
+            .collect();
+        kdas.sort_by(|a, b| {
+            order
+                .apply(a.kda.total_cmp(&b.kda))
+                .then_with(|| a.summoner_id.cmp(&b.summoner_id))
+        });
+        kdas
+    }
+
+    /// `stat` per minute of game time for every summoner with at least `cutoff` games.
+    #[must_use]
+    pub fn stat_per_min(&self, stat: PerMinuteStat, cutoff: u32, order: Order) -> Vec<StatPerMin> {
+        let mut totals: HashMap<u64, (SummonerTotals, i64, u64)> = HashMap::new();
+        for game in self.games() {
+            for (player, participant) in participants(game) {
+                let (totals, value, seconds) = totals
+                    .entry(player.summoner_id)
+                    .or_insert_with(|| (SummonerTotals::new(player), 0, 0));
+                totals.summoner_name.clone_from(&player.summoner_name);
+                totals.games += 1;
+                *value += stat.value(participant);
+                *seconds += u64::from(game.game_duration);
+            }
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let mut stats: Vec<_> = totals
+            .into_values()
+            .filter(|(x, _, _)| x.games >= cutoff)
+            .map(|(x, value, seconds)| StatPerMin {
+                summoner_id: x.summoner_id,
+                summoner_name: x.summoner_name,
+                games: x.games,
+                stat: if seconds == 0 {
+                    0.0
+                } else {
+                    value as f64 / (seconds as f64 / 60.0)
+                },
+            })
+            .collect();
+        stats.sort_by(|a, b| {
+            order
+                .apply(a.stat.total_cmp(&b.stat))
+                .then_with(|| a.summoner_id.cmp(&b.summoner_id))
+        });
+        stats
+    }
+
+    /// Champions a summoner has won at least `cutoff` games on without ever losing,
+    /// ordered by wins.
+    #[must_use]
+    pub fn lossless_champions(&self, cutoff: u32, order: Order) -> Vec<SummonerChampion> {
+        let mut champions: Vec<_> = self
+            .champion_totals()
+            .into_values()
+            .filter(|x| x.losses == 0 && x.wins >= cutoff)
+            .collect();
+        champions.sort_by(|a, b| {
+            order
+                .apply(a.wins.cmp(&b.wins))
+                .then_with(|| a.summoner_name.cmp(&b.summoner_name))
+                .then_with(|| a.champion_id.cmp(&b.champion_id))
+        });
+        champions
+    }
+
+    /// Per champion results of a summoner, including champions only banned in their
+    /// games.
+    #[must_use]
+    pub fn summoner_stats(&self, summoner_id: u64) -> Vec<ChampionStats> {
+        let mut stats: BTreeMap<i32, ChampionStats> = BTreeMap::new();
+        for game in self.games() {
+            let Some((_, participant)) =
+                participants(game).find(|(player, _)| player.summoner_id == summoner_id)
+            else {
+                continue;
+            };
+
+            let entry = stats
+                .entry(participant.champion_id)
+                .or_insert_with(|| ChampionStats::new(participant.champion_id, summoner_id));
+            if participant.stats.win {
+                entry.wins += 1;
+            } else {
+                entry.losses += 1;
+            }
+            entry.kills += participant.stats.kills;
+            entry.deaths += participant.stats.deaths;
+            entry.assists += participant.stats.assists;
+
+            // A champion banned by both teams counts once per game
+            let banned: HashSet<_> = game
+                .teams
+                .iter()
+                .flat_map(|team| &team.bans)
+                .map(|ban| ban.champion_id)
+                .filter(|&champion_id| champion_id > 0)
+                .collect();
+            for champion_id in banned {
+                stats
+                    .entry(champion_id)
+                    .or_insert_with(|| ChampionStats::new(champion_id, summoner_id))
+                    .bans += 1;
+            }
+        }
+        stats.into_values().collect()
+    }
+
+    /// Games a summoner played, newest first, skipping the first `offset`.
+    #[must_use]
+    pub fn summoner_matches(
+        &self,
+        summoner_id: u64,
+        amount: usize,
+        offset: usize,
+    ) -> Vec<&LolMatchHistoryMatchHistoryGame> {
+        self.games()
+            .rev()
+            .filter(|game| {
+                game.participant_identities
+                    .iter()
+                    .any(|x| x.player.summoner_id == summoner_id)
+            })
+            .skip(offset)
+            .take(amount)
+            .collect()
+    }
+
+    fn summoner_totals(&self) -> HashMap<u64, SummonerTotals> {
+        let mut totals: HashMap<u64, SummonerTotals> = HashMap::new();
+        for game in self.games() {
+            for (player, participant) in participants(game) {
+                let totals = totals
+                    .entry(player.summoner_id)
+                    .or_insert_with(|| SummonerTotals::new(player));
+                // Games are oldest first, so this ends up as the most recent name
+                totals.summoner_name.clone_from(&player.summoner_name);
+                totals.games += 1;
+                totals.wins += u32::from(participant.stats.win);
+                totals.kills += participant.stats.kills;
+                totals.deaths += participant.stats.deaths;
+                totals.assists += participant.stats.assists;
+                totals.champions.insert(participant.champion_id);
+            }
+        }
+        totals
+    }
+
+    fn champion_totals(&self) -> HashMap<(u64, i32), SummonerChampion> {
+        let names: HashMap<_, _> = self
+            .summoner_totals()
+            .into_iter()
+            .map(|(id, x)| (id, x.summoner_name))
+            .collect();
+
+        let mut totals: HashMap<(u64, i32), SummonerChampion> = HashMap::new();
+        for game in self.games() {
+            for (player, participant) in participants(game) {
+                let totals = totals
+                    .entry((player.summoner_id, participant.champion_id))
+                    .or_insert_with(|| SummonerChampion {
+                        champion_id: participant.champion_id,
+                        summoner_id: player.summoner_id,
+                        summoner_name: names.get(&player.summoner_id).cloned().unwrap_or_default(),
+                        wins: 0,
+                        losses: 0,
+                    });
+                if participant.stats.win {
+                    totals.wins += 1;
+                } else {
+                    totals.losses += 1;
+                }
+            }
+        }
+        totals
+    }
+}
+
+/// Pairs every participant in `game` with the player behind it.
+pub fn participants(
+    game: &LolMatchHistoryMatchHistoryGame,
+) -> impl Iterator<
+    Item = (
+        &LolMatchHistoryMatchHistoryParticipantIdentityPlayer,
+        &LolMatchHistoryMatchHistoryParticipant,
+    ),
+> {
+    game.participant_identities.iter().filter_map(|identity| {
+        game.participants
+            .iter()
+            .find(|x| x.participant_id == identity.participant_id)
+            .map(|participant| (&identity.player, participant))
+    })
+}
+
+/// Kills and assists per death of totals summed over several games, or kills and
+/// assists if there were no deaths. Use
+/// [`crate::types::LolMatchHistoryMatchHistoryParticipantStatistics::kda`] for a single
+/// game.
+#[must_use]
+pub fn kda(kills: i64, deaths: i64, assists: i64) -> f64 {
+    kda_ratio(kills, deaths, assists)
+}
+
+pub(crate) fn ratio(part: u32, total: u32) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        f64::from(part) / f64::from(total)
+    }
+}
+
+struct SummonerTotals {
+    summoner_id: u64,
+    summoner_name: String,
+    games: u32,
+    wins: u32,
+    kills: i64,
+    deaths: i64,
+    assists: i64,
+    champions: HashSet<i32>,
+}
+
+impl SummonerTotals {
+    fn new(player: &LolMatchHistoryMatchHistoryParticipantIdentityPlayer) -> Self {
+        Self {
+            summoner_id: player.summoner_id,
+            summoner_name: player.summoner_name.clone(),
+            games: 0,
+            wins: 0,
+            kills: 0,
+            deaths: 0,
+            assists: 0,
+            champions: HashSet::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Order {
+    Ascending,
+    #[default]
+    Descending,
+}
+
+impl Order {
+    pub(crate) fn apply(self, ordering: Ordering) -> Ordering {
+        match self {
+            Self::Ascending => ordering,
+            Self::Descending => ordering.reverse(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SummonerOrder {
+    #[default]
+    Games,
+    Winrate,
+    ChampionsPlayed,
+}
+
+/// Stats available to [`MatchStore::stat_per_min`], named as in the stats api routes.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PerMinuteStat {
+    /// Lane minions and jungle monsters.
+    MinionsKilled,
+    /// Damage dealt to champions.
+    DamageDealt,
+    DamageTaken,
+    DamageToTurrets,
+    DamageToObjectives,
+    GoldEarned,
+    VisionScore,
+}
+
+impl PerMinuteStat {
+    pub const ALL: [Self; 7] = [
+        Self::MinionsKilled,
+        Self::DamageDealt,
+        Self::DamageTaken,
+        Self::DamageToTurrets,
+        Self::DamageToObjectives,
+        Self::GoldEarned,
+        Self::VisionScore,
+    ];
+
+    #[must_use]
+    pub fn value(self, participant: &LolMatchHistoryMatchHistoryParticipant) -> i64 {
+        let stats = &participant.stats;
+        match self {
+            Self::MinionsKilled => stats.cs(),
+            Self::DamageDealt => stats.total_damage_dealt_to_champions,
+            Self::DamageTaken => stats.total_damage_taken,
+            Self::DamageToTurrets => stats.damage_dealt_to_turrets,
+            Self::DamageToObjectives => stats.damage_dealt_to_objectives,
+            Self::GoldEarned => stats.gold_earned,
+            Self::VisionScore => stats.vision_score,
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MinionsKilled => "minions_killed",
+            Self::DamageDealt => "damage_dealt",
+            Self::DamageTaken => "damage_taken",
+            Self::DamageToTurrets => "damage_to_turrets",
+            Self::DamageToObjectives => "damage_to_objectives",
+            Self::GoldEarned => "gold_earned",
+            Self::VisionScore => "vision_score",
+        }
+    }
+}
+
+impl FromStr for PerMinuteStat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.as_str() == s)
+            .ok_or_else(|| Error::Custom(format!("Unknown stat {s}")))
+    }
+}
+
+impl Display for PerMinuteStat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SummonerSummary {
+    pub summoner_id: u64,
+    pub summoner_name: String,
+    pub games: u32,
+    pub winrate: f64,
+    /// Number of distinct champions played.
+    pub champs: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Kda {
+    pub summoner_id: u64,
+    pub summoner_name: String,
+    pub games: u32,
+    pub kills: i64,
+    pub deaths: i64,
+    pub assists: i64,
+    pub kda: f64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StatPerMin {
+    pub summoner_id: u64,
+    pub summoner_name: String,
+    pub games: u32,
+    pub stat: f64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SummonerChampion {
+    pub champion_id: i32,
+    pub summoner_id: u64,
+    pub summoner_name: String,
+    pub wins: u32,
+    pub losses: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampionStats {
+    pub champion_id: i32,
+    pub summoner_id: u64,
+    pub wins: u32,
+    pub losses: u32,
+    pub kills: i64,
+    pub deaths: i64,
+    pub assists: i64,
+    /// Games of the summoner where the champion was banned by either team.
+    pub bans: u32,
+}
+
+impl ChampionStats {
+    fn new(champion_id: i32, summoner_id: u64) -> Self {
+        Self {
+            champion_id,
+            summoner_id,
+            wins: 0,
+            losses: 0,
+            kills: 0,
+            deaths: 0,
+            assists: 0,
+            bans: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{MatchStore, Order, PerMinuteStat, SummonerOrder};
+    use crate::types::{
+        LolMatchHistoryMatchHistoryGame, LolMatchHistoryMatchHistoryParticipant,
+        LolMatchHistoryMatchHistoryParticipantIdentities,
+        LolMatchHistoryMatchHistoryParticipantIdentityPlayer,
+        LolMatchHistoryMatchHistoryParticipantStatistics, LolMatchHistoryMatchHistoryTeam,
+        LolMatchHistoryMatchHistoryTeamBan,
+    };
+
+    /// A 10 minute game where summoners `1..=5` on team 100 beat summoners `6..=10`.
+    /// Each entry of `champions` is played by the summoner at the same position, and
+    /// summoner `n` goes `n / 0 / 1` with `100 * n` damage.
+    pub(crate) fn game(
+        game_id: u64,
+        summoners: [u64; 10],
+        champions: [i32; 10],
+    ) -> LolMatchHistoryMatchHistoryGame {
+        LolMatchHistoryMatchHistoryGame {
+            game_id,
+            game_creation: game_id * 1000,
+            game_duration: 600,
+            teams: vec![
+                LolMatchHistoryMatchHistoryTeam {
+                    team_id: 100,
+                    win: "Win".to_owned(),
+                    bans: vec![LolMatchHistoryMatchHistoryTeamBan {
+                        champion_id: 99,
+                        pick_turn: 1,
+                    }],
+                    ..Default::default()
+                },
+                LolMatchHistoryMatchHistoryTeam {
+                    team_id: 200,
+                    win: "Fail".to_owned(),
+                    ..Default::default()
+                },
+            ],
+            participants: (0..10)
+                .map(|i| LolMatchHistoryMatchHistoryParticipant {
+                    participant_id: i + 1,
+                    team_id: if i < 5 { 100 } else { 200 },
+                    champion_id: champions[i as usize],
+                    stats: LolMatchHistoryMatchHistoryParticipantStatistics {
+                        participant_id: i + 1,
+                        win: i < 5,
+                        kills: summoners[i as usize].try_into().unwrap(),
+                        assists: 1,
+                        total_damage_dealt_to_champions: i64::try_from(summoners[i as usize])
+                            .unwrap()
+                            * 100,
+                        total_minions_killed: 50,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .collect(),
+            participant_identities: (0..10)
+                .map(|i| LolMatchHistoryMatchHistoryParticipantIdentities {
+                    participant_id: i + 1,
+                    player: LolMatchHistoryMatchHistoryParticipantIdentityPlayer {
+                        summoner_id: summoners[i as usize],
+                        summoner_name: format!("Summoner {}", summoners[i as usize]),
+                        ..Default::default()
+                    },
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn store() -> MatchStore {
+        [
+            game(
+                1,
+                [1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+                [1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+            ),
+            game(
+                2,
+                [1, 2, 3, 4, 6, 5, 7, 8, 9, 10],
+                [1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+            ),
+            game(
+                3,
+                [6, 2, 3, 4, 5, 1, 7, 8, 9, 10],
+                [1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+            ),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn summoner_names() {
+        let store = store();
+
+        let by_games = store.summoner_names(SummonerOrder::Games, 3, Order::Descending);
+        assert_eq!(by_games.len(), 10);
+
+        let by_winrate = store.summoner_names(SummonerOrder::Winrate, 0, Order::Descending);
+        let top: Vec<_> = by_winrate.iter().take(3).map(|x| x.summoner_id).collect();
+        assert_eq!(top, [2, 3, 4]);
+        assert_eq!(by_winrate.last().unwrap().winrate, 0.0);
+
+        let summoner = store.summoner(1).unwrap();
+        assert_eq!(summoner.summoner_name, "Summoner 1");
+        assert_eq!(summoner.games, 3);
+        assert!((summoner.winrate - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(summoner.champs, 2);
+    }
+
+    #[test]
+    fn kdas_and_stats_per_minute() {
+        let store = store();
+
+        let kdas = store.kdas(0, Order::Descending);
+        assert_eq!(kdas[0].summoner_id, 10);
+        assert!((kdas[0].kda - 33.0).abs() < 1e-9);
+        assert_eq!(store.kdas(0, Order::Ascending)[0].summoner_id, 1);
+
+        let damage = store.stat_per_min(PerMinuteStat::DamageDealt, 0, Order::Descending);
+        assert_eq!(damage[0].summoner_id, 10);
+        assert!((damage[0].stat - 100.0).abs() < 1e-9);
+        assert_eq!(
+            "damage_dealt".parse::<PerMinuteStat>().unwrap(),
+            PerMinuteStat::DamageDealt
+        );
+        assert!("nope".parse::<PerMinuteStat>().is_err());
+    }
+
+    #[test]
+    fn lossless_champions() {
+        let pairs = |order| {
+            store()
+                .lossless_champions(2, order)
+                .iter()
+                .map(|x| (x.summoner_id, x.champion_id, x.wins))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            pairs(Order::Descending),
+            [(2, 2, 3), (3, 3, 3), (4, 4, 3), (1, 1, 2), (5, 5, 2)]
+        );
+        assert_eq!(
+            pairs(Order::Ascending),
+            [(1, 1, 2), (5, 5, 2), (2, 2, 3), (3, 3, 3), (4, 4, 3)]
+        );
+    }
+
+    #[test]
+    fn summoner_stats_and_matches() {
+        let store = store();
+
+        let stats = store.summoner_stats(1);
+        let ids: Vec<_> = stats.iter().map(|x| x.champion_id).collect();
+        assert_eq!(ids, [1, 6, 99]);
+        assert_eq!((stats[0].wins, stats[0].losses, stats[0].kills), (2, 0, 2));
+        assert_eq!(stats[2].bans, 3);
+
+        let matches: Vec<_> = store
+            .summoner_matches(5, 20, 0)
+            .iter()
+            .map(|x| x.game_id)
+            .collect();
+        assert_eq!(matches, [3, 2, 1]);
+        assert_eq!(store.summoner_matches(5, 1, 1)[0].game_id, 2);
+    }
+
+    #[test]
+    fn bans_count_once_per_game() {
+        let mut game = game(
+            1,
+            [1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+            [1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+        );
+        game.teams[1].bans = [99, -1]
+            .map(|champion_id| LolMatchHistoryMatchHistoryTeamBan {
+                champion_id,
+                pick_turn: 2,
+            })
+            .into();
+        let store: MatchStore = [game].into_iter().collect();
+
+        let stats = store.summoner_stats(1);
+        let ids: Vec<_> = stats.iter().map(|x| x.champion_id).collect();
+        assert_eq!(ids, [1, 99]);
+        assert_eq!(stats[1].bans, 1);
+    }
+}
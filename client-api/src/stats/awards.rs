@@ -0,0 +1,446 @@
+use super::{participants, Order};
+use crate::{
+    types::{LolMatchHistoryMatchHistoryGame, LolMatchHistoryMatchHistoryParticipant},
+    Error,
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, fmt::Write, str::FromStr};
+
+/// Stats awards and the MVP score can be based on.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AwardStat {
+    Kills,
+    Deaths,
+    Assists,
+    Kda,
+    /// Share of the team's kills the player took part in.
+    KillParticipation,
+    /// Damage dealt to champions.
+    DamageDealt,
+    DamageTaken,
+    DamageMitigated,
+    DamageToObjectives,
+    Healing,
+    /// Lane minions and jungle monsters.
+    MinionsKilled,
+    GoldEarned,
+    VisionScore,
+    /// Seconds spent crowd controlling enemy champions.
+    CrowdControl,
+}
+
+impl AwardStat {
+    pub const ALL: [Self; 14] = [
+        Self::Kills,
+        Self::Deaths,
+        Self::Assists,
+        Self::Kda,
+        Self::KillParticipation,
+        Self::DamageDealt,
+        Self::DamageTaken,
+        Self::DamageMitigated,
+        Self::DamageToObjectives,
+        Self::Healing,
+        Self::MinionsKilled,
+        Self::GoldEarned,
+        Self::VisionScore,
+        Self::CrowdControl,
+    ];
+
+    /// Value of the stat for `participant` in `game`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn value(
+        self,
+        game: &LolMatchHistoryMatchHistoryGame,
+        participant: &LolMatchHistoryMatchHistoryParticipant,
+    ) -> f64 {
+        let stats = &participant.stats;
+        let value = match self {
+            Self::Kills => stats.kills,
+            Self::Deaths => stats.deaths,
+            Self::Assists => stats.assists,
+            Self::Kda => return stats.kda(),
+            Self::KillParticipation => return participant.kill_participation(game),
+            Self::DamageDealt => stats.total_damage_dealt_to_champions,
+            Self::DamageTaken => stats.total_damage_taken,
+            Self::DamageMitigated => stats.damage_self_mitigated,
+            Self::DamageToObjectives => stats.damage_dealt_to_objectives,
+            Self::Healing => stats.total_heal,
+            Self::MinionsKilled => stats.cs(),
+            Self::GoldEarned => stats.gold_earned,
+            Self::VisionScore => stats.vision_score,
+            Self::CrowdControl => stats.time_c_cing_others,
+        };
+        value as f64
+    }
+
+    /// Formats `value` for chat, e.g. kill participation as a percentage.
+    #[must_use]
+    pub fn format_value(self, value: f64) -> String {
+        match self {
+            Self::Kda => format!("{value:.2}"),
+            Self::KillParticipation => format!("{:.0}%", value * 100.0),
+            Self::CrowdControl => format!("{value:.0}s"),
+            _ => format!("{value:.0}"),
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Kills => "kills",
+            Self::Deaths => "deaths",
+            Self::Assists => "assists",
+            Self::Kda => "kda",
+            Self::KillParticipation => "kill_participation",
+            Self::DamageDealt => "damage_dealt",
+            Self::DamageTaken => "damage_taken",
+            Self::DamageMitigated => "damage_mitigated",
+            Self::DamageToObjectives => "damage_to_objectives",
+            Self::Healing => "healing",
+            Self::MinionsKilled => "minions_killed",
+            Self::GoldEarned => "gold_earned",
+            Self::VisionScore => "vision_score",
+            Self::CrowdControl => "crowd_control",
+        }
+    }
+}
+
+impl FromStr for AwardStat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|x| x.as_str() == s)
+            .ok_or_else(|| Error::Custom(format!("Unknown stat {s}")))
+    }
+}
+
+impl Display for AwardStat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An award given to the player with the highest, or with [`Order::Ascending`] the
+/// lowest, value of `stat`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AwardRule {
+    pub name: String,
+    pub stat: AwardStat,
+    #[serde(default)]
+    pub order: Order,
+}
+
+impl AwardRule {
+    #[must_use]
+    pub fn new(name: impl Into<String>, stat: AwardStat, order: Order) -> Self {
+        Self {
+            name: name.into(),
+            stat,
+            order,
+        }
+    }
+}
+
+/// Contribution of a stat to the MVP score.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MvpWeight {
+    pub stat: AwardStat,
+    #[serde(default)]
+    pub order: Order,
+    pub weight: f64,
+}
+
+impl MvpWeight {
+    #[must_use]
+    pub const fn new(stat: AwardStat, order: Order, weight: f64) -> Self {
+        Self {
+            stat,
+            order,
+            weight,
+        }
+    }
+}
+
+/// Awards to hand out and how the MVP score is computed.
+///
+/// Every weighted stat is scaled between the worst and the best player of the game, so
+/// the MVP score is between 0 and 10 plus `win_bonus` for players on the winning team.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AwardsConfig {
+    pub awards: Vec<AwardRule>,
+    pub mvp: Vec<MvpWeight>,
+    #[serde(default)]
+    pub win_bonus: f64,
+}
+
+impl Default for AwardsConfig {
+    fn default() -> Self {
+        Self {
+            awards: vec![
+                AwardRule::new("Most damage", AwardStat::DamageDealt, Order::Descending),
+                AwardRule::new("Best vision", AwardStat::VisionScore, Order::Descending),
+                AwardRule::new(
+                    "Most crowd control",
+                    AwardStat::CrowdControl,
+                    Order::Descending,
+                ),
+                AwardRule::new("Fewest deaths", AwardStat::Deaths, Order::Ascending),
+                AwardRule::new(
+                    "Highest kill participation",
+                    AwardStat::KillParticipation,
+                    Order::Descending,
+                ),
+            ],
+            mvp: vec![
+                MvpWeight::new(AwardStat::Kda, Order::Descending, 3.0),
+                MvpWeight::new(AwardStat::KillParticipation, Order::Descending, 2.0),
+                MvpWeight::new(AwardStat::DamageDealt, Order::Descending, 2.0),
+                MvpWeight::new(AwardStat::VisionScore, Order::Descending, 1.0),
+                MvpWeight::new(AwardStat::CrowdControl, Order::Descending, 1.0),
+                MvpWeight::new(AwardStat::GoldEarned, Order::Descending, 1.0),
+            ],
+            win_bonus: 1.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Award {
+    pub name: String,
+    pub stat: AwardStat,
+    pub summoner_id: u64,
+    pub summoner_name: String,
+    pub champion_id: i32,
+    pub value: f64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MvpScore {
+    pub summoner_id: u64,
+    pub summoner_name: String,
+    pub champion_id: i32,
+    pub team_id: u32,
+    pub score: f64,
+}
+
+/// Awards and MVP scores of one game.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GameAwards {
+    pub game_id: u64,
+    pub awards: Vec<Award>,
+    /// Every player, highest score first.
+    pub mvp_scores: Vec<MvpScore>,
+}
+
+impl GameAwards {
+    #[must_use]
+    pub fn mvp(&self) -> Option<&MvpScore> {
+        self.mvp_scores.first()
+    }
+
+    /// Formats the MVP and the awards for the lobby chat.
+    #[must_use]
+    pub fn to_chat(&self) -> String {
+        let mut out = ".".to_owned();
+        if let Some(mvp) = self.mvp() {
+            let _ = write!(out, "\nMVP: {} ({:.1})", mvp.summoner_name, mvp.score);
+        }
+        for award in &self.awards {
+            let _ = write!(
+                out,
+                "\n{}: {} ({})",
+                award.name,
+                award.summoner_name,
+                award.stat.format_value(award.value)
+            );
+        }
+        out
+    }
+}
+
+impl AwardsConfig {
+    /// Hands out the awards of `game` and scores every player. Ties go to the player
+    /// with the lowest participant id.
+    #[must_use]
+    pub fn evaluate(&self, game: &LolMatchHistoryMatchHistoryGame) -> GameAwards {
+        let players: Vec<_> = participants(game).collect();
+
+        let awards = self
+            .awards
+            .iter()
+            .filter_map(|rule| {
+                let (player, participant, value) = players
+                    .iter()
+                    .map(|(player, participant)| {
+                        (player, participant, rule.stat.value(game, participant))
+                    })
+                    .min_by(|a, b| {
+                        rule.order
+                            .apply(a.2.total_cmp(&b.2))
+                            .then_with(|| a.1.participant_id.cmp(&b.1.participant_id))
+                    })?;
+                Some(Award {
+                    name: rule.name.clone(),
+                    stat: rule.stat,
+                    summoner_id: player.summoner_id,
+                    summoner_name: player.summoner_name.clone(),
+                    champion_id: participant.champion_id,
+                    value,
+                })
+            })
+            .collect();
+
+        let total_weight: f64 = self.mvp.iter().map(|x| x.weight).sum();
+        let scaled: Vec<Vec<f64>> = self
+            .mvp
+            .iter()
+            .map(|weight| {
+                let values: Vec<_> = players
+                    .iter()
+                    .map(|(_, participant)| weight.stat.value(game, participant))
+                    .collect();
+                let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+                let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+                values
+                    .into_iter()
+                    .map(|value| {
+                        let scaled = if max > min {
+                            (value - min) / (max - min)
+                        } else {
+                            1.0
+                        };
+                        match weight.order {
+                            Order::Ascending => 1.0 - scaled,
+                            Order::Descending => scaled,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut mvp_scores: Vec<_> = players
+            .iter()
+            .enumerate()
+            .map(|(i, (player, participant))| {
+                let weighted: f64 = self
+                    .mvp
+                    .iter()
+                    .zip(&scaled)
+                    .map(|(weight, values)| weight.weight * values[i])
+                    .sum();
+                let score = if total_weight > 0.0 {
+                    10.0 * weighted / total_weight
+                } else {
+                    0.0
+                };
+                MvpScore {
+                    summoner_id: player.summoner_id,
+                    summoner_name: player.summoner_name.clone(),
+                    champion_id: participant.champion_id,
+                    team_id: participant.team_id,
+                    score: score
+                        + if participant.stats.win {
+                            self.win_bonus
+                        } else {
+                            0.0
+                        },
+                }
+            })
+            .collect();
+        mvp_scores.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        GameAwards {
+            game_id: game.game_id,
+            awards,
+            mvp_scores,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AwardRule, AwardStat, AwardsConfig, MvpWeight};
+    use crate::stats::{tests::game, Order};
+
+    #[test]
+    fn default_awards() {
+        let mut game = game(1, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10], [1; 10]);
+        game.participants[6].stats.vision_score = 40;
+        game.participants[2].stats.time_c_cing_others = 30;
+        for participant in &mut game.participants {
+            participant.stats.deaths = 3;
+        }
+        game.participants[3].stats.deaths = 1;
+
+        let awards = AwardsConfig::default().evaluate(&game);
+        let winners: Vec<_> = awards
+            .awards
+            .iter()
+            .map(|x| (x.name.as_str(), x.summoner_id))
+            .collect();
+        assert_eq!(
+            winners,
+            [
+                ("Most damage", 10),
+                ("Best vision", 7),
+                ("Most crowd control", 3),
+                ("Fewest deaths", 4),
+                // 5 kills and 1 assist out of 15 team kills
+                ("Highest kill participation", 5),
+            ]
+        );
+        assert_eq!(awards.awards[4].value, 6.0 / 15.0);
+        assert_eq!(awards.mvp_scores.len(), 10);
+        assert!(awards.mvp().unwrap().score <= 11.0);
+
+        let chat = awards.to_chat();
+        assert!(chat.starts_with(".\nMVP: "));
+        assert!(chat.contains("\nMost crowd control: Summoner 3 (30s)"));
+        assert!(chat.ends_with("\nHighest kill participation: Summoner 5 (40%)"));
+    }
+
+    #[test]
+    fn configured_rules() {
+        let game = game(1, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10], [1; 10]);
+        let config: AwardsConfig = serde_json::from_str(
+            r#"{
+                "awards": [{"name": "Pacifist", "stat": "kills", "order": "ascending"}],
+                "mvp": [{"stat": "kills", "weight": 1.0}]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.awards,
+            [AwardRule::new(
+                "Pacifist",
+                AwardStat::Kills,
+                Order::Ascending
+            )]
+        );
+        assert_eq!(
+            config.mvp,
+            [MvpWeight::new(AwardStat::Kills, Order::Descending, 1.0)]
+        );
+
+        let awards = config.evaluate(&game);
+        assert_eq!(awards.awards[0].summoner_id, 1);
+        let mvp = awards.mvp().unwrap();
+        assert_eq!((mvp.summoner_id, mvp.score), (10, 10.0));
+        assert_eq!(awards.mvp_scores[9].score, 0.0);
+        assert_eq!(
+            "crowd_control".parse::<AwardStat>().unwrap(),
+            AwardStat::CrowdControl
+        );
+    }
+}
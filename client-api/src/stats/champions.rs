@@ -0,0 +1,235 @@
+use super::{kda, participants, ratio, MatchStore};
+use crate::{types::LolMatchHistoryMatchHistoryGame, Error};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    io::Write,
+};
+
+/// Results of a champion, either over every game or over the games of one summoner.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampionAggregate {
+    pub champion_id: i32,
+    /// Set when only the games of this summoner are counted.
+    pub summoner_id: Option<u64>,
+    /// Games the champion was picked in.
+    pub games: u32,
+    pub wins: u32,
+    pub losses: u32,
+    /// Games the champion was banned in.
+    pub bans: u32,
+    pub pick_rate: f64,
+    pub ban_rate: f64,
+    pub winrate: f64,
+    /// Average per game.
+    pub kills: f64,
+    /// Average per game.
+    pub deaths: f64,
+    /// Average per game.
+    pub assists: f64,
+    pub kda: f64,
+    /// Damage to champions per minute.
+    pub damage_per_min: f64,
+    pub gold_per_min: f64,
+}
+
+#[derive(Default)]
+struct Totals {
+    games: u32,
+    wins: u32,
+    bans: u32,
+    kills: i64,
+    deaths: i64,
+    assists: i64,
+    damage: i64,
+    gold: i64,
+    seconds: u64,
+}
+
+impl MatchStore {
+    /// Every champion picked or banned in any game, most picked first.
+    #[must_use]
+    pub fn champion_aggregates(&self) -> Vec<ChampionAggregate> {
+        aggregate(self.games(), None)
+    }
+
+    /// Every champion `summoner_id` played, or that was banned in their games, most
+    /// picked first. Rates are relative to the summoner's games.
+    #[must_use]
+    pub fn summoner_champion_aggregates(&self, summoner_id: u64) -> Vec<ChampionAggregate> {
+        aggregate(
+            self.games().filter(|game| {
+                participants(game).any(|(player, _)| player.summoner_id == summoner_id)
+            }),
+            Some(summoner_id),
+        )
+    }
+}
+
+fn aggregate<'a>(
+    games: impl Iterator<Item = &'a LolMatchHistoryMatchHistoryGame>,
+    summoner_id: Option<u64>,
+) -> Vec<ChampionAggregate> {
+    let mut total_games = 0;
+    let mut totals: BTreeMap<i32, Totals> = BTreeMap::new();
+    for game in games {
+        total_games += 1;
+
+        let banned: HashSet<_> = game
+            .teams
+            .iter()
+            .flat_map(|team| &team.bans)
+            .map(|ban| ban.champion_id)
+            .filter(|&champion_id| champion_id > 0)
+            .collect();
+        for champion_id in banned {
+            totals.entry(champion_id).or_default().bans += 1;
+        }
+
+        for (player, participant) in participants(game) {
+            if summoner_id.is_some_and(|x| x != player.summoner_id) {
+                continue;
+            }
+            let totals = totals.entry(participant.champion_id).or_default();
+            totals.games += 1;
+            totals.wins += u32::from(participant.stats.win);
+            totals.kills += participant.stats.kills;
+            totals.deaths += participant.stats.deaths;
+            totals.assists += participant.stats.assists;
+            totals.damage += participant.stats.total_damage_dealt_to_champions;
+            totals.gold += participant.stats.gold_earned;
+            totals.seconds += u64::from(game.game_duration);
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let mut aggregates: Vec<_> = totals
+        .into_iter()
+        .map(|(champion_id, x)| {
+            let per_game = |value: i64| {
+                if x.games == 0 {
+                    0.0
+                } else {
+                    value as f64 / f64::from(x.games)
+                }
+            };
+            let per_min = |value: i64| {
+                if x.seconds == 0 {
+                    0.0
+                } else {
+                    value as f64 / (x.seconds as f64 / 60.0)
+                }
+            };
+            ChampionAggregate {
+                champion_id,
+                summoner_id,
+                games: x.games,
+                wins: x.wins,
+                losses: x.games - x.wins,
+                bans: x.bans,
+                pick_rate: ratio(x.games, total_games),
+                ban_rate: ratio(x.bans, total_games),
+                winrate: ratio(x.wins, x.games),
+                kills: per_game(x.kills),
+                deaths: per_game(x.deaths),
+                assists: per_game(x.assists),
+                kda: kda(x.kills, x.deaths, x.assists),
+                damage_per_min: per_min(x.damage),
+                gold_per_min: per_min(x.gold),
+            }
+        })
+        .collect();
+    aggregates.sort_by(|a, b| {
+        b.games
+            .cmp(&a.games)
+            .then_with(|| b.bans.cmp(&a.bans))
+            .then_with(|| a.champion_id.cmp(&b.champion_id))
+    });
+    aggregates
+}
+
+/// Writes `aggregates` as csv with a header row.
+///
+/// # Errors
+/// Fails if `writer` cannot be written to.
+pub fn write_csv(aggregates: &[ChampionAggregate], writer: impl Write) -> Result<(), Error> {
+    let mut writer = csv::Writer::from_writer(writer);
+    for aggregate in aggregates {
+        writer.serialize(aggregate)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_csv;
+    use crate::stats::{tests::game, MatchStore};
+
+    fn store() -> MatchStore {
+        let mut games = [
+            game(
+                1,
+                [1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+                [1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+            ),
+            game(
+                2,
+                [1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+                [6, 2, 3, 4, 5, 1, 7, 8, 9, 10],
+            ),
+        ];
+        for game in &mut games {
+            for participant in &mut game.participants {
+                participant.stats.gold_earned = 5000;
+            }
+        }
+        games.into_iter().collect()
+    }
+
+    #[test]
+    fn global_aggregates() {
+        let aggregates = store().champion_aggregates();
+        let one = aggregates.iter().find(|x| x.champion_id == 1).unwrap();
+        assert_eq!((one.games, one.wins, one.losses), (2, 1, 1));
+        assert_eq!(one.pick_rate, 1.0);
+        assert_eq!(one.winrate, 0.5);
+        // Summoner 1 gets 1 kill and summoner 6 gets 6 kills, both with 1 assist
+        assert_eq!(one.kills, 3.5);
+        assert_eq!(one.kda, 9.0);
+        assert_eq!(one.damage_per_min, 35.0);
+        assert_eq!(one.gold_per_min, 500.0);
+
+        let banned = aggregates.iter().find(|x| x.champion_id == 99).unwrap();
+        assert_eq!((banned.games, banned.bans), (0, 2));
+        assert_eq!(banned.ban_rate, 1.0);
+        assert_eq!(banned.winrate, 0.0);
+    }
+
+    #[test]
+    fn summoner_aggregates() {
+        let aggregates = store().summoner_champion_aggregates(6);
+        let ids: Vec<_> = aggregates.iter().map(|x| x.champion_id).collect();
+        assert_eq!(ids, [1, 6, 99]);
+        assert_eq!(aggregates[0].summoner_id, Some(6));
+        assert_eq!(aggregates[0].pick_rate, 0.5);
+        assert_eq!(aggregates[0].wins, 0);
+        assert!(store().summoner_champion_aggregates(11).is_empty());
+    }
+
+    #[test]
+    fn csv_output() -> Result<(), crate::Error> {
+        let mut csv = Vec::new();
+        write_csv(&store().summoner_champion_aggregates(1), &mut csv)?;
+        let csv = String::from_utf8(csv).unwrap();
+        let mut lines = csv.lines();
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("championId,summonerId,games,wins,losses,bans,pickRate"));
+        assert!(lines.next().unwrap().starts_with("1,1,1,1,0,0,0.5,"));
+        assert_eq!(lines.count(), 2);
+        Ok(())
+    }
+}
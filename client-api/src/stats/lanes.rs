@@ -0,0 +1,426 @@
+use super::{participants, MatchStore, Order};
+use crate::types::{LolMatchHistoryMatchHistoryGame, LolMatchHistoryMatchHistoryParticipant};
+use serde::{Deserialize, Serialize};
+use std::collections::{btree_map::Entry, BTreeMap, HashMap};
+
+/// Timeline window the leads are taken from, in minutes.
+const EARLY_GAME: &str = "0-10";
+const EARLY_GAME_MINUTES: f64 = 10.0;
+const SMITE: u32 = 11;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Position {
+    Top,
+    Jungle,
+    Middle,
+    Bottom,
+    Support,
+}
+
+impl Position {
+    pub const ALL: [Self; 5] = [
+        Self::Top,
+        Self::Jungle,
+        Self::Middle,
+        Self::Bottom,
+        Self::Support,
+    ];
+
+    /// Reads the position from the timeline lane and role, if they say anything.
+    #[must_use]
+    pub fn from_timeline(lane: &str, role: &str) -> Option<Self> {
+        match (lane, role) {
+            ("TOP", _) => Some(Self::Top),
+            ("JUNGLE", _) => Some(Self::Jungle),
+            ("MIDDLE" | "MID", _) => Some(Self::Middle),
+            ("BOTTOM" | "BOT", "DUO_SUPPORT" | "SUPPORT") => Some(Self::Support),
+            ("BOTTOM" | "BOT", _) => Some(Self::Bottom),
+            _ => None,
+        }
+    }
+}
+
+/// Early game result of one player against the opponent in the same position.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LaneMatchup {
+    pub game_id: u64,
+    pub position: Position,
+    pub summoner_id: u64,
+    pub summoner_name: String,
+    pub champion_id: i32,
+    pub opponent_summoner_id: u64,
+    pub opponent_champion_id: i32,
+    /// Creep score ahead of the opponent at 10 minutes.
+    pub cs_lead: f64,
+    /// Experience ahead of the opponent at 10 minutes.
+    pub xp_lead: f64,
+    /// Gold ahead of the opponent at 10 minutes.
+    pub gold_lead: f64,
+    pub win: bool,
+}
+
+/// Average early game leads of a player against their lane opponents.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerLaneLeads {
+    pub summoner_id: u64,
+    pub summoner_name: String,
+    pub games: u32,
+    pub cs_lead: f64,
+    pub xp_lead: f64,
+    pub gold_lead: f64,
+}
+
+/// Average early game leads of a champion against another in the same position.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChampionMatchup {
+    pub champion_id: i32,
+    pub opponent_champion_id: i32,
+    pub games: u32,
+    pub wins: u32,
+    pub cs_lead: f64,
+    pub xp_lead: f64,
+    pub gold_lead: f64,
+}
+
+#[derive(Default)]
+struct Leads {
+    games: u32,
+    wins: u32,
+    cs: f64,
+    xp: f64,
+    gold: f64,
+}
+
+impl Leads {
+    fn add(&mut self, matchup: &LaneMatchup) {
+        self.games += 1;
+        self.wins += u32::from(matchup.win);
+        self.cs += matchup.cs_lead;
+        self.xp += matchup.xp_lead;
+        self.gold += matchup.gold_lead;
+    }
+
+    fn average(&self, total: f64) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            total / f64::from(self.games)
+        }
+    }
+}
+
+impl MatchStore {
+    /// Every player paired with their lane opponent in every game, oldest game first.
+    /// Games where the positions cannot be worked out are skipped.
+    #[must_use]
+    pub fn lane_matchups(&self) -> Vec<LaneMatchup> {
+        self.games().flat_map(lane_matchups).collect()
+    }
+
+    /// Players with at least `cutoff` lane matchups, ordered by gold lead.
+    #[must_use]
+    pub fn player_lane_leads(&self, cutoff: u32, order: Order) -> Vec<PlayerLaneLeads> {
+        let mut totals: HashMap<u64, (String, Leads)> = HashMap::new();
+        for matchup in self.lane_matchups() {
+            let (name, leads) = totals.entry(matchup.summoner_id).or_default();
+            name.clone_from(&matchup.summoner_name);
+            leads.add(&matchup);
+        }
+
+        let mut leads: Vec<_> = totals
+            .into_iter()
+            .filter(|(_, (_, x))| x.games >= cutoff)
+            .map(|(summoner_id, (summoner_name, x))| PlayerLaneLeads {
+                summoner_id,
+                summoner_name,
+                games: x.games,
+                cs_lead: x.average(x.cs),
+                xp_lead: x.average(x.xp),
+                gold_lead: x.average(x.gold),
+            })
+            .collect();
+        leads.sort_by(|a, b| {
+            order
+                .apply(a.gold_lead.total_cmp(&b.gold_lead))
+                .then_with(|| a.summoner_id.cmp(&b.summoner_id))
+        });
+        leads
+    }
+
+    /// Champion pairs that met in lane at least `cutoff` times, from the point of view of
+    /// both champions, ordered by gold lead.
+    #[must_use]
+    pub fn champion_matchups(&self, cutoff: u32, order: Order) -> Vec<ChampionMatchup> {
+        let mut totals: BTreeMap<(i32, i32), Leads> = BTreeMap::new();
+        for matchup in self.lane_matchups() {
+            totals
+                .entry((matchup.champion_id, matchup.opponent_champion_id))
+                .or_default()
+                .add(&matchup);
+        }
+
+        let mut matchups: Vec<_> = totals
+            .into_iter()
+            .filter(|(_, x)| x.games >= cutoff)
+            .map(|((champion_id, opponent_champion_id), x)| ChampionMatchup {
+                champion_id,
+                opponent_champion_id,
+                games: x.games,
+                wins: x.wins,
+                cs_lead: x.average(x.cs),
+                xp_lead: x.average(x.xp),
+                gold_lead: x.average(x.gold),
+            })
+            .collect();
+        matchups.sort_by(|a, b| {
+            order
+                .apply(a.gold_lead.total_cmp(&b.gold_lead))
+                .then_with(|| a.champion_id.cmp(&b.champion_id))
+                .then_with(|| a.opponent_champion_id.cmp(&b.opponent_champion_id))
+        });
+        matchups
+    }
+}
+
+/// Pairs lane opponents in `game`, from the point of view of every player.
+#[must_use]
+pub fn lane_matchups(game: &LolMatchHistoryMatchHistoryGame) -> Vec<LaneMatchup> {
+    let positions = guess_positions(game);
+    let players: Vec<_> = participants(game).collect();
+    let mut matchups = Vec::new();
+    for (player, participant) in &players {
+        let Some(&position) = positions.get(&participant.participant_id) else {
+            continue;
+        };
+        let Some((opponent, opponent_participant)) = players.iter().find(|(_, x)| {
+            x.team_id != participant.team_id && positions.get(&x.participant_id) == Some(&position)
+        }) else {
+            continue;
+        };
+
+        matchups.push(LaneMatchup {
+            game_id: game.game_id,
+            position,
+            summoner_id: player.summoner_id,
+            summoner_name: player.summoner_name.clone(),
+            champion_id: participant.champion_id,
+            opponent_summoner_id: opponent.summoner_id,
+            opponent_champion_id: opponent_participant.champion_id,
+            cs_lead: lead(participant, opponent_participant, |x| {
+                &x.timeline.creeps_per_min_deltas
+            })
+            .or_else(|| early(&participant.timeline.cs_diff_per_min_deltas))
+            .unwrap_or_default(),
+            xp_lead: lead(participant, opponent_participant, |x| {
+                &x.timeline.xp_per_min_deltas
+            })
+            .or_else(|| early(&participant.timeline.xp_diff_per_min_deltas))
+            .unwrap_or_default(),
+            gold_lead: lead(participant, opponent_participant, |x| {
+                &x.timeline.gold_per_min_deltas
+            })
+            .unwrap_or_default(),
+            win: participant.stats.win,
+        });
+    }
+    matchups
+}
+
+fn early(deltas: &HashMap<String, f64>) -> Option<f64> {
+    deltas.get(EARLY_GAME).map(|x| x * EARLY_GAME_MINUTES)
+}
+
+fn lead(
+    participant: &LolMatchHistoryMatchHistoryParticipant,
+    opponent: &LolMatchHistoryMatchHistoryParticipant,
+    deltas: impl Fn(&LolMatchHistoryMatchHistoryParticipant) -> &HashMap<String, f64>,
+) -> Option<f64> {
+    Some(early(deltas(participant))? - early(deltas(opponent))?)
+}
+
+/// Position of every participant in `game`, by participant id. Positions come from the
+/// timeline lane and role when they are set and unique within the team. Otherwise the
+/// player with smite, or the most jungle monsters, is the jungler, the player with the
+/// fewest lane minions is the support, and the rest fill the open positions in
+/// participant order. Teams that are not five players are left out.
+#[must_use]
+pub fn guess_positions(game: &LolMatchHistoryMatchHistoryGame) -> HashMap<u32, Position> {
+    let mut teams: BTreeMap<u32, Vec<&LolMatchHistoryMatchHistoryParticipant>> = BTreeMap::new();
+    for participant in &game.participants {
+        teams
+            .entry(participant.team_id)
+            .or_default()
+            .push(participant);
+    }
+
+    let mut positions = HashMap::new();
+    for team in teams
+        .values()
+        .filter(|team| team.len() == Position::ALL.len())
+    {
+        positions.extend(guess_team_positions(team));
+    }
+    positions
+}
+
+fn guess_team_positions(
+    team: &[&LolMatchHistoryMatchHistoryParticipant],
+) -> HashMap<u32, Position> {
+    let mut assigned: BTreeMap<Position, &LolMatchHistoryMatchHistoryParticipant> = BTreeMap::new();
+    let mut unassigned = Vec::new();
+    for &participant in team {
+        let timeline = &participant.timeline;
+        match Position::from_timeline(&timeline.lane, &timeline.role) {
+            Some(position) if !assigned.contains_key(&position) => {
+                assigned.insert(position, participant);
+            }
+            // Two players claiming the same lane, the one farming it keeps it
+            Some(position) => {
+                let current = assigned[&position];
+                if participant.stats.total_minions_killed > current.stats.total_minions_killed {
+                    assigned.insert(position, participant);
+                    unassigned.push(current);
+                } else {
+                    unassigned.push(participant);
+                }
+            }
+            None => unassigned.push(participant),
+        }
+    }
+
+    if let Entry::Vacant(entry) = assigned.entry(Position::Jungle) {
+        let jungler = unassigned
+            .iter()
+            .position(|x| x.spell1_id == SMITE || x.spell2_id == SMITE)
+            .or_else(|| {
+                unassigned
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|(_, x)| x.stats.neutral_minions_killed)
+                    .map(|(i, _)| i)
+            });
+        if let Some(i) = jungler {
+            entry.insert(unassigned.remove(i));
+        }
+    }
+    if let Entry::Vacant(entry) = assigned.entry(Position::Support) {
+        let support = unassigned
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, x)| x.stats.total_minions_killed)
+            .map(|(i, _)| i);
+        if let Some(i) = support {
+            entry.insert(unassigned.remove(i));
+        }
+    }
+    let mut unassigned = unassigned.into_iter();
+    for position in Position::ALL {
+        if let Entry::Vacant(entry) = assigned.entry(position) {
+            if let Some(participant) = unassigned.next() {
+                entry.insert(participant);
+            }
+        }
+    }
+
+    assigned
+        .into_iter()
+        .map(|(position, participant)| (participant.participant_id, position))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{guess_positions, Position};
+    use crate::stats::{tests::game, MatchStore, Order};
+    use crate::types::LolMatchHistoryMatchHistoryGame;
+
+    const LANES: [(&str, &str); 5] = [
+        ("TOP", "SOLO"),
+        ("JUNGLE", "NONE"),
+        ("MIDDLE", "SOLO"),
+        ("BOTTOM", "DUO_CARRY"),
+        ("BOTTOM", "DUO_SUPPORT"),
+    ];
+
+    /// Every player gets `6 * summoner` creeps and gold per minute.
+    fn laned_game(game_id: u64, summoners: [u64; 10]) -> LolMatchHistoryMatchHistoryGame {
+        let mut game = game(game_id, summoners, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        for (i, participant) in game.participants.iter_mut().enumerate() {
+            let (lane, role) = LANES[i % 5];
+            let per_min = 6.0 * summoners[i] as f64;
+            participant.timeline.lane = lane.to_owned();
+            participant.timeline.role = role.to_owned();
+            participant.timeline.creeps_per_min_deltas = [("0-10".to_owned(), per_min)].into();
+            participant.timeline.gold_per_min_deltas = [("0-10".to_owned(), per_min)].into();
+            participant.timeline.xp_diff_per_min_deltas = [("0-10".to_owned(), 1.5)].into();
+        }
+        game
+    }
+
+    #[test]
+    fn positions_from_timeline() {
+        let game = laned_game(1, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        let positions = guess_positions(&game);
+        assert_eq!(positions[&1], Position::Top);
+        assert_eq!(positions[&5], Position::Support);
+        assert_eq!(positions[&10], Position::Support);
+    }
+
+    #[test]
+    fn guesses_missing_positions() {
+        let mut game = laned_game(1, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        for participant in &mut game.participants {
+            participant.timeline.lane = "NONE".to_owned();
+        }
+        game.participants[2].spell2_id = 11;
+        game.participants[0].stats.total_minions_killed = 10;
+        game.participants[7].stats.neutral_minions_killed = 80;
+        game.participants[6].stats.total_minions_killed = 5;
+
+        let positions = guess_positions(&game);
+        assert_eq!(positions[&3], Position::Jungle);
+        assert_eq!(positions[&1], Position::Support);
+        assert_eq!(positions[&2], Position::Top);
+        assert_eq!(positions[&8], Position::Jungle);
+        assert_eq!(positions[&7], Position::Support);
+        assert_eq!(positions.len(), 10);
+    }
+
+    #[test]
+    fn leads_by_player_and_champion() {
+        let store: MatchStore = [
+            laned_game(1, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]),
+            laned_game(2, [6, 7, 8, 9, 10, 1, 2, 3, 4, 5]),
+        ]
+        .into_iter()
+        .collect();
+
+        let matchups = store.lane_matchups();
+        assert_eq!(matchups.len(), 20);
+        let top = &matchups[0];
+        assert_eq!((top.summoner_id, top.opponent_summoner_id), (1, 6));
+        assert_eq!(top.cs_lead, -300.0);
+        assert_eq!(top.xp_lead, 15.0);
+
+        let leads = store.player_lane_leads(2, Order::Descending);
+        assert_eq!(leads[0].summoner_id, 6);
+        assert_eq!(leads[0].gold_lead, 300.0);
+        assert_eq!(leads[9].gold_lead, -300.0);
+        let leads = store.player_lane_leads(2, Order::Ascending);
+        assert_eq!(leads[0].gold_lead, -300.0);
+
+        let champions = store.champion_matchups(2, Order::Descending);
+        assert!(champions[0].gold_lead >= champions[9].gold_lead);
+        assert_eq!(champions.len(), 10);
+        let one_vs_six = champions
+            .iter()
+            .find(|x| (x.champion_id, x.opponent_champion_id) == (1, 6))
+            .unwrap();
+        assert_eq!((one_vs_six.games, one_vs_six.wins), (2, 2));
+        assert_eq!(one_vs_six.cs_lead, 0.0);
+    }
+}
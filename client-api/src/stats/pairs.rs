@@ -0,0 +1,218 @@
+use super::{participants, ratio, MatchStore, Order};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Results of two players in the games they both played, from the point of view of
+/// the first player.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PairStats {
+    pub summoner_id: u64,
+    pub summoner_name: String,
+    pub other_summoner_id: u64,
+    pub other_summoner_name: String,
+    /// Games on the same team.
+    pub games_together: u32,
+    pub wins_together: u32,
+    pub winrate_together: f64,
+    /// Games on opposing teams.
+    pub games_against: u32,
+    /// Games the first player won against the other.
+    pub wins_against: u32,
+    pub winrate_against: f64,
+}
+
+impl PairStats {
+    fn new(summoner_id: u64, other_summoner_id: u64) -> Self {
+        Self {
+            summoner_id,
+            summoner_name: String::new(),
+            other_summoner_id,
+            other_summoner_name: String::new(),
+            games_together: 0,
+            wins_together: 0,
+            winrate_together: 0.0,
+            games_against: 0,
+            wins_against: 0,
+            winrate_against: 0.0,
+        }
+    }
+
+    /// The same results from the point of view of the other player.
+    #[must_use]
+    pub fn flipped(self) -> Self {
+        Self {
+            summoner_id: self.other_summoner_id,
+            summoner_name: self.other_summoner_name,
+            other_summoner_id: self.summoner_id,
+            other_summoner_name: self.summoner_name,
+            wins_against: self.games_against - self.wins_against,
+            winrate_against: ratio(self.games_against - self.wins_against, self.games_against),
+            ..self
+        }
+    }
+}
+
+impl MatchStore {
+    /// Pairs that played at least `cutoff` games on the same team, ordered by their
+    /// winrate together. Every pair is listed once, lowest summoner id first.
+    #[must_use]
+    pub fn duos(&self, cutoff: u32, order: Order) -> Vec<PairStats> {
+        let mut duos: Vec<_> = self
+            .pair_totals()
+            .into_values()
+            .filter(|x| x.games_together >= cutoff && x.games_together > 0)
+            .collect();
+        duos.sort_by(|a, b| {
+            order
+                .apply(
+                    a.winrate_together
+                        .total_cmp(&b.winrate_together)
+                        .then_with(|| a.games_together.cmp(&b.games_together)),
+                )
+                .then_with(|| {
+                    (a.summoner_id, a.other_summoner_id).cmp(&(b.summoner_id, b.other_summoner_id))
+                })
+        });
+        duos
+    }
+
+    /// Results of `summoner_id` with and against every player they have played with,
+    /// most games first.
+    #[must_use]
+    pub fn summoner_pairs(&self, summoner_id: u64) -> Vec<PairStats> {
+        let mut pairs: Vec<_> = self
+            .pair_totals()
+            .into_values()
+            .filter_map(|x| {
+                if x.summoner_id == summoner_id {
+                    Some(x)
+                } else if x.other_summoner_id == summoner_id {
+                    Some(x.flipped())
+                } else {
+                    None
+                }
+            })
+            .collect();
+        pairs.sort_by(|a, b| {
+            (b.games_together + b.games_against)
+                .cmp(&(a.games_together + a.games_against))
+                .then_with(|| a.other_summoner_id.cmp(&b.other_summoner_id))
+        });
+        pairs
+    }
+
+    /// Results of `summoner_id` with and against `other_summoner_id`, or `None` if they
+    /// have never played in the same game.
+    #[must_use]
+    pub fn head_to_head(&self, summoner_id: u64, other_summoner_id: u64) -> Option<PairStats> {
+        self.summoner_pairs(summoner_id)
+            .into_iter()
+            .find(|x| x.other_summoner_id == other_summoner_id)
+    }
+
+    fn pair_totals(&self) -> BTreeMap<(u64, u64), PairStats> {
+        let mut names = HashMap::new();
+        let mut totals: BTreeMap<(u64, u64), PairStats> = BTreeMap::new();
+        for game in self.games() {
+            let players: Vec<_> = participants(game).collect();
+            for (i, (player, participant)) in players.iter().enumerate() {
+                names.insert(player.summoner_id, player.summoner_name.clone());
+                for (other, other_participant) in &players[i + 1..] {
+                    let (first, second, first_won) = if player.summoner_id < other.summoner_id {
+                        (player, other, participant.stats.win)
+                    } else {
+                        (other, player, other_participant.stats.win)
+                    };
+                    if first.summoner_id == second.summoner_id {
+                        continue;
+                    }
+
+                    let pair = totals
+                        .entry((first.summoner_id, second.summoner_id))
+                        .or_insert_with(|| PairStats::new(first.summoner_id, second.summoner_id));
+                    if participant.team_id == other_participant.team_id {
+                        pair.games_together += 1;
+                        pair.wins_together += u32::from(first_won);
+                    } else {
+                        pair.games_against += 1;
+                        pair.wins_against += u32::from(first_won);
+                    }
+                }
+            }
+        }
+
+        for pair in totals.values_mut() {
+            pair.summoner_name = names.get(&pair.summoner_id).cloned().unwrap_or_default();
+            pair.other_summoner_name = names
+                .get(&pair.other_summoner_id)
+                .cloned()
+                .unwrap_or_default();
+            pair.winrate_together = ratio(pair.wins_together, pair.games_together);
+            pair.winrate_against = ratio(pair.wins_against, pair.games_against);
+        }
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stats::{tests::game, MatchStore, Order};
+
+    fn store() -> MatchStore {
+        [
+            game(1, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10], [1; 10]),
+            game(2, [1, 2, 3, 4, 6, 5, 7, 8, 9, 10], [1; 10]),
+            game(3, [6, 2, 3, 4, 5, 1, 7, 8, 9, 10], [1; 10]),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn duos() {
+        let store = store();
+        let duos = store.duos(3, Order::Descending);
+        let best = &duos[0];
+        assert_eq!((best.summoner_id, best.other_summoner_id), (2, 3));
+        assert_eq!(best.games_together, 3);
+        assert_eq!(best.winrate_together, 1.0);
+        assert!(duos.iter().all(|x| x.games_together >= 3));
+
+        let worst = &store.duos(3, Order::Ascending)[0];
+        assert_eq!(worst.winrate_together, 0.0);
+        assert_eq!(worst.summoner_name, "Summoner 7");
+    }
+
+    #[test]
+    fn head_to_head() {
+        let store = store();
+        let one_vs_seven = store.head_to_head(1, 7).unwrap();
+        assert_eq!(
+            (one_vs_seven.games_together, one_vs_seven.wins_together),
+            (1, 0)
+        );
+        assert_eq!(
+            (one_vs_seven.games_against, one_vs_seven.wins_against),
+            (2, 2)
+        );
+
+        let seven_vs_one = store.head_to_head(7, 1).unwrap();
+        assert_eq!(seven_vs_one.summoner_name, "Summoner 7");
+        assert_eq!(seven_vs_one.wins_against, 0);
+        assert_eq!(seven_vs_one.winrate_against, 0.0);
+
+        let one_and_six = store.head_to_head(1, 6).unwrap();
+        assert_eq!(
+            (one_and_six.games_together, one_and_six.wins_together),
+            (1, 1)
+        );
+        assert_eq!(
+            (one_and_six.games_against, one_and_six.wins_against),
+            (2, 1)
+        );
+
+        assert!(store.head_to_head(1, 11).is_none());
+        assert_eq!(store.summoner_pairs(1).len(), 9);
+    }
+}
@@ -0,0 +1,561 @@
+use super::participants;
+use crate::{
+    types::{
+        LolMatchHistoryMatchHistoryGame, LolMatchHistoryMatchHistoryParticipantIdentityPlayer,
+    },
+    Error,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    f64::consts::{PI, SQRT_2},
+    fmt::Display,
+};
+
+/// Identifies a player across games.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum PlayerId {
+    SummonerId(u64),
+    Puuid(String),
+}
+
+impl Display for PlayerId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SummonerId(id) => write!(f, "{id}"),
+            Self::Puuid(puuid) => f.write_str(puuid),
+        }
+    }
+}
+
+/// Which id players are tracked by. Puuids stay the same across regions and name
+/// changes, but are missing from older exported games.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyBy {
+    #[default]
+    SummonerId,
+    Puuid,
+}
+
+impl KeyBy {
+    #[must_use]
+    pub fn player_id(
+        self,
+        player: &LolMatchHistoryMatchHistoryParticipantIdentityPlayer,
+    ) -> Option<PlayerId> {
+        match self {
+            Self::SummonerId => Some(PlayerId::SummonerId(player.summoner_id)),
+            Self::Puuid if player.puuid.is_empty() => None,
+            Self::Puuid => Some(PlayerId::Puuid(player.puuid.clone())),
+        }
+    }
+}
+
+/// A way of updating player ratings from the result of a two team game.
+pub trait RatingSystem {
+    type Rating: Clone + std::fmt::Debug;
+
+    /// Rating of a player without any games.
+    fn initial(&self) -> Self::Rating;
+
+    /// Updates the ratings of every player in a game won by `winners`.
+    fn update(&self, winners: &mut [Self::Rating], losers: &mut [Self::Rating]);
+
+    /// Single number used to rank players and balance teams.
+    fn score(&self, rating: &Self::Rating) -> f64;
+}
+
+/// Elo with teams rated by their average rating.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Elo {
+    pub initial: f64,
+    /// Most a rating can change in one game.
+    pub k: f64,
+}
+
+impl Default for Elo {
+    fn default() -> Self {
+        Self {
+            initial: 1500.0,
+            k: 32.0,
+        }
+    }
+}
+
+impl RatingSystem for Elo {
+    type Rating = f64;
+
+    fn initial(&self) -> f64 {
+        self.initial
+    }
+
+    fn update(&self, winners: &mut [f64], losers: &mut [f64]) {
+        let expected = 1.0 / (1.0 + 10f64.powf((mean(losers) - mean(winners)) / 400.0));
+        let change = self.k * (1.0 - expected);
+        winners.iter_mut().for_each(|x| *x += change);
+        losers.iter_mut().for_each(|x| *x -= change);
+    }
+
+    fn score(&self, rating: &f64) -> f64 {
+        *rating
+    }
+}
+
+/// TrueSkill for two teams without draws.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrueSkill {
+    pub mu: f64,
+    pub sigma: f64,
+    /// Skill difference that gives the better player about a 76% chance to win.
+    pub beta: f64,
+    /// Uncertainty added before every game so ratings keep moving.
+    pub tau: f64,
+}
+
+impl Default for TrueSkill {
+    fn default() -> Self {
+        Self {
+            mu: 25.0,
+            sigma: 25.0 / 3.0,
+            beta: 25.0 / 6.0,
+            tau: 25.0 / 300.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct Gaussian {
+    pub mu: f64,
+    pub sigma: f64,
+}
+
+impl RatingSystem for TrueSkill {
+    type Rating = Gaussian;
+
+    fn initial(&self) -> Gaussian {
+        Gaussian {
+            mu: self.mu,
+            sigma: self.sigma,
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn update(&self, winners: &mut [Gaussian], losers: &mut [Gaussian]) {
+        for rating in winners.iter_mut().chain(losers.iter_mut()) {
+            rating.sigma = rating.sigma.hypot(self.tau);
+        }
+
+        let players = (winners.len() + losers.len()) as f64;
+        let c = (winners
+            .iter()
+            .chain(losers.iter())
+            .map(|x| x.sigma.powi(2))
+            .sum::<f64>()
+            + players * self.beta.powi(2))
+        .sqrt();
+        let t = (winners.iter().map(|x| x.mu).sum::<f64>()
+            - losers.iter().map(|x| x.mu).sum::<f64>())
+            / c;
+        let v = normal_pdf(t) / normal_cdf(t).max(f64::MIN_POSITIVE);
+        let w = v * (v + t);
+
+        for (ratings, sign) in [(winners, 1.0), (losers, -1.0)] {
+            for rating in ratings {
+                let variance = rating.sigma.powi(2);
+                rating.mu += sign * variance / c * v;
+                rating.sigma = (variance * (1.0 - variance / c.powi(2) * w))
+                    .max(0.0)
+                    .sqrt();
+            }
+        }
+    }
+
+    /// Conservative estimate that is very likely below the true skill.
+    fn score(&self, rating: &Gaussian) -> f64 {
+        rating.mu - 3.0 * rating.sigma
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RatingPoint<R> {
+    pub game_id: u64,
+    pub game_creation: u64,
+    /// Rating after the game.
+    pub rating: R,
+    pub score: f64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerRating<R> {
+    pub player_id: PlayerId,
+    /// Name in the player's most recent game.
+    pub summoner_name: String,
+    pub rating: R,
+    pub games: u32,
+    pub wins: u32,
+    pub history: Vec<RatingPoint<R>>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderboardEntry {
+    pub player_id: PlayerId,
+    pub summoner_name: String,
+    pub score: f64,
+    pub games: u32,
+    pub wins: u32,
+}
+
+/// Ratings of every player, built up game by game.
+#[derive(Debug, Clone)]
+pub struct Ratings<S: RatingSystem> {
+    system: S,
+    key_by: KeyBy,
+    players: HashMap<PlayerId, PlayerRating<S::Rating>>,
+}
+
+impl<S: RatingSystem> Ratings<S> {
+    pub fn new(system: S, key_by: KeyBy) -> Self {
+        Self {
+            system,
+            key_by,
+            players: HashMap::new(),
+        }
+    }
+
+    /// Rates `games` oldest first.
+    pub fn from_games<'a>(
+        system: S,
+        key_by: KeyBy,
+        games: impl IntoIterator<Item = &'a LolMatchHistoryMatchHistoryGame>,
+    ) -> Self {
+        let mut games: Vec<_> = games.into_iter().collect();
+        games.sort_by_key(|game| (game.game_creation, game.game_id));
+
+        let mut ratings = Self::new(system, key_by);
+        for game in games {
+            ratings.add_game(game);
+        }
+        ratings
+    }
+
+    /// Updates ratings with the result of `game`, which must be newer than every game
+    /// added before. Only 5v5 games where every player can be identified are rated.
+    /// Returns whether the game was rated.
+    pub fn add_game(&mut self, game: &LolMatchHistoryMatchHistoryGame) -> bool {
+        if game.participants.len() != 10 {
+            return false;
+        }
+        let Some(players) = participants(game)
+            .map(|(player, participant)| {
+                let id = self.key_by.player_id(player)?;
+                Some((id, player, participant.stats.win))
+            })
+            .collect::<Option<Vec<_>>>()
+        else {
+            return false;
+        };
+        let (winners, losers): (Vec<_>, Vec<_>) = players.into_iter().partition(|x| x.2);
+        if winners.len() != 5 || losers.len() != 5 {
+            return false;
+        }
+
+        let rating_of = |players: &[(PlayerId, _, bool)]| -> Vec<S::Rating> {
+            players
+                .iter()
+                .map(|(id, _, _)| {
+                    self.players
+                        .get(id)
+                        .map_or_else(|| self.system.initial(), |x| x.rating.clone())
+                })
+                .collect()
+        };
+        let mut winner_ratings = rating_of(&winners);
+        let mut loser_ratings = rating_of(&losers);
+        self.system.update(&mut winner_ratings, &mut loser_ratings);
+
+        for ((id, player, win), rating) in winners
+            .into_iter()
+            .zip(winner_ratings)
+            .chain(losers.into_iter().zip(loser_ratings))
+        {
+            let entry = self
+                .players
+                .entry(id.clone())
+                .or_insert_with(|| PlayerRating {
+                    player_id: id,
+                    summoner_name: String::new(),
+                    rating: rating.clone(),
+                    games: 0,
+                    wins: 0,
+                    history: Vec::new(),
+                });
+            entry.summoner_name.clone_from(&player.summoner_name);
+            entry.games += 1;
+            entry.wins += u32::from(win);
+            entry.history.push(RatingPoint {
+                game_id: game.game_id,
+                game_creation: game.game_creation,
+                score: self.system.score(&rating),
+                rating: rating.clone(),
+            });
+            entry.rating = rating;
+        }
+        true
+    }
+
+    #[must_use]
+    pub fn player(&self, player_id: &PlayerId) -> Option<&PlayerRating<S::Rating>> {
+        self.players.get(player_id)
+    }
+
+    /// Ratings of a player after each of their games, oldest first.
+    #[must_use]
+    pub fn history(&self, player_id: &PlayerId) -> &[RatingPoint<S::Rating>] {
+        self.players
+            .get(player_id)
+            .map_or(&[], |x| x.history.as_slice())
+    }
+
+    /// Players with at least `min_games` games, highest score first.
+    #[must_use]
+    pub fn leaderboard(&self, min_games: u32) -> Vec<LeaderboardEntry> {
+        let mut entries: Vec<_> = self
+            .players
+            .values()
+            .filter(|x| x.games >= min_games)
+            .map(|x| LeaderboardEntry {
+                player_id: x.player_id.clone(),
+                summoner_name: x.summoner_name.clone(),
+                score: self.system.score(&x.rating),
+                games: x.games,
+                wins: x.wins,
+            })
+            .collect();
+        entries.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.player_id.cmp(&b.player_id))
+        });
+        entries
+    }
+}
+
+/// Skill estimates used to balance teams.
+pub trait RatingSource {
+    /// Skill of a player, or `None` for unknown players.
+    fn rating(&self, player_id: &PlayerId) -> Option<f64>;
+
+    /// Skill assumed for unknown players.
+    fn default_rating(&self) -> f64;
+}
+
+impl<S: RatingSystem> RatingSource for Ratings<S> {
+    fn rating(&self, player_id: &PlayerId) -> Option<f64> {
+        self.players
+            .get(player_id)
+            .map(|x| self.system.score(&x.rating))
+    }
+
+    fn default_rating(&self) -> f64 {
+        self.system.score(&self.system.initial())
+    }
+}
+
+impl RatingSource for HashMap<PlayerId, f64> {
+    fn rating(&self, player_id: &PlayerId) -> Option<f64> {
+        self.get(player_id).copied()
+    }
+
+    fn default_rating(&self) -> f64 {
+        mean(&self.values().copied().collect::<Vec<_>>())
+    }
+}
+
+/// Most players [`balance_teams`] splits, enough for a 16 player arena lobby.
+pub const MAX_BALANCED_PLAYERS: usize = 16;
+
+/// Splits `players` into the two teams with the smallest difference in total rating.
+/// With an odd number of players the first team gets the extra player.
+///
+/// # Errors
+/// Every split is tried, so this fails for more than [`MAX_BALANCED_PLAYERS`] players.
+pub fn balance_teams<'a, T>(
+    players: &'a [T],
+    player_id: impl Fn(&T) -> PlayerId,
+    source: &impl RatingSource,
+) -> Result<(Vec<&'a T>, Vec<&'a T>), Error> {
+    if players.len() > MAX_BALANCED_PLAYERS {
+        return Err(Error::TooManyPlayers(players.len()));
+    }
+    let ratings: Vec<_> = players
+        .iter()
+        .map(|x| {
+            source
+                .rating(&player_id(x))
+                .unwrap_or_else(|| source.default_rating())
+        })
+        .collect();
+    let total: f64 = ratings.iter().sum();
+    let team_size = players.len().div_ceil(2);
+
+    // Players are at most 16, so every split can be tried. The first player is
+    // always on the first team to skip mirrored splits.
+    let mut best = (f64::INFINITY, 0_u32);
+    for mask in 0..1_u32 << players.len() {
+        if mask & 1 == 0 || mask.count_ones() as usize != team_size {
+            continue;
+        }
+        let team: f64 = (0..players.len())
+            .filter(|i| mask & (1 << i) != 0)
+            .map(|i| ratings[i])
+            .sum();
+        let difference = (total - 2.0 * team).abs();
+        if difference < best.0 {
+            best = (difference, mask);
+        }
+    }
+
+    let mut teams = (Vec::new(), Vec::new());
+    for (i, player) in players.iter().enumerate() {
+        if best.1 & (1 << i) == 0 {
+            teams.1.push(player);
+        } else {
+            teams.0.push(player);
+        }
+    }
+    Ok(teams)
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let len = values.len() as f64;
+    values.iter().sum::<f64>() / len
+}
+
+fn normal_pdf(x: f64) -> f64 {
+    (-x * x / 2.0).exp() / (2.0 * PI).sqrt()
+}
+
+fn normal_cdf(x: f64) -> f64 {
+    erfc(-x / SQRT_2) / 2.0
+}
+
+/// Complementary error function with a fractional error below 1.2e-7, from Numerical
+/// Recipes.
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + z / 2.0);
+    let r = t
+        * (-z * z - 1.265_512_23
+            + t * (1.000_023_68
+                + t * (0.374_091_96
+                    + t * (0.096_784_18
+                        + t * (-0.186_288_06
+                            + t * (0.278_868_07
+                                + t * (-1.135_203_98
+                                    + t * (1.488_515_87
+                                        + t * (-0.822_152_23 + t * 0.170_872_77)))))))))
+            .exp();
+    if x >= 0.0 {
+        r
+    } else {
+        2.0 - r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{balance_teams, Elo, KeyBy, PlayerId, RatingSource, Ratings, TrueSkill};
+    use crate::{stats::tests::game, Error};
+    use std::collections::HashMap;
+
+    const SUMMONERS: [u64; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+    const CHAMPIONS: [i32; 10] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+
+    #[test]
+    fn elo_moves_winners_up() {
+        let games = [game(2, SUMMONERS, CHAMPIONS), game(1, SUMMONERS, CHAMPIONS)];
+        let ratings = Ratings::from_games(Elo::default(), KeyBy::SummonerId, &games);
+
+        let winner = ratings.player(&PlayerId::SummonerId(1)).unwrap();
+        assert_eq!(winner.games, 2);
+        assert!((winner.history[0].rating - 1516.0).abs() < 1e-9);
+        assert!(winner.rating > 1516.0 && winner.rating < 1532.0);
+        assert_eq!(
+            ratings.history(&PlayerId::SummonerId(1))[1].game_id,
+            2,
+            "history is oldest first"
+        );
+
+        let leaderboard = ratings.leaderboard(2);
+        assert_eq!(leaderboard.len(), 10);
+        assert_eq!(leaderboard[0].player_id, PlayerId::SummonerId(1));
+        assert!(leaderboard[9].score < 1500.0);
+    }
+
+    #[test]
+    fn trueskill_reduces_uncertainty() {
+        let games = [game(1, SUMMONERS, CHAMPIONS)];
+        let ratings = Ratings::from_games(TrueSkill::default(), KeyBy::SummonerId, &games);
+
+        let winner = ratings.player(&PlayerId::SummonerId(1)).unwrap().rating;
+        let loser = ratings.player(&PlayerId::SummonerId(6)).unwrap().rating;
+        assert!(winner.mu > 25.0 && loser.mu < 25.0);
+        assert!((winner.mu - 25.0 - (25.0 - loser.mu)).abs() < 1e-9);
+        assert!(winner.sigma < 25.0 / 3.0);
+        assert!(ratings.rating(&PlayerId::SummonerId(1)).unwrap() > ratings.default_rating());
+    }
+
+    #[test]
+    fn puuids_are_required_when_keyed_by_puuid() {
+        let mut with_puuids = game(1, SUMMONERS, CHAMPIONS);
+        for identity in &mut with_puuids.participant_identities {
+            identity.player.puuid = format!("puuid-{}", identity.player.summoner_id);
+        }
+        let games = [game(2, SUMMONERS, CHAMPIONS), with_puuids];
+        let ratings = Ratings::from_games(Elo::default(), KeyBy::Puuid, &games);
+        let player = ratings
+            .player(&PlayerId::Puuid("puuid-3".to_owned()))
+            .unwrap();
+        assert_eq!(player.games, 1);
+    }
+
+    #[test]
+    fn balances_teams() {
+        let ratings: HashMap<_, _> = [(1, 10.0), (2, 9.0), (3, 1.0), (4, 1.0)]
+            .into_iter()
+            .map(|(id, rating)| (PlayerId::SummonerId(id), rating))
+            .collect();
+        let players = [1, 2, 3, 4, 5, 6];
+        let (first, second) =
+            balance_teams(&players, |x| PlayerId::SummonerId(*x), &ratings).unwrap();
+        assert_eq!(first.len(), 3);
+        assert_eq!(second.len(), 3);
+
+        let total = |team: &[&u64]| -> f64 {
+            team.iter()
+                .map(|x| {
+                    ratings
+                        .rating(&PlayerId::SummonerId(**x))
+                        .unwrap_or_else(|| ratings.default_rating())
+                })
+                .sum()
+        };
+        assert!((total(&first) - total(&second)).abs() < 1.0 + 1e-9);
+        assert!(first.contains(&&1) != first.contains(&&2));
+
+        let players: Vec<u64> = (0..=16).collect();
+        assert!(matches!(
+            balance_teams(&players, |x| PlayerId::SummonerId(*x), &ratings),
+            Err(Error::TooManyPlayers(17))
+        ));
+    }
+}
@@ -0,0 +1,89 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+/// A request received by [`serve_recording`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<String>,
+    pub body: String,
+}
+
+/// Serves `files` over http on a random local port, returning the base url.
+pub(crate) fn serve(files: Vec<(&'static str, String)>) -> String {
+    serve_recording(files).0
+}
+
+/// Serves `files` over http on a random local port, returning the base url and every
+/// request received so far.
+pub(crate) fn serve_recording(
+    files: Vec<(&'static str, String)>,
+) -> (String, Arc<Mutex<Vec<Request>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}/", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let received = requests.clone();
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(&stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut request_parts = request_line.split(' ');
+            let mut request = Request {
+                method: request_parts.next().unwrap_or_default().to_owned(),
+                path: request_parts.next().unwrap_or_default().to_owned(),
+                ..Default::default()
+            };
+            let mut header = String::new();
+            while reader.read_line(&mut header).is_ok_and(|n| n > 2) {
+                request.headers.push(header.trim_end().to_owned());
+                header.clear();
+            }
+            let content_length = request
+                .headers
+                .iter()
+                .find_map(|h| {
+                    h.to_lowercase()
+                        .strip_prefix("content-length:")?
+                        .trim()
+                        .parse()
+                        .ok()
+                })
+                .unwrap_or(0);
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            request.body = String::from_utf8_lossy(&body).into_owned();
+
+            // Prefer an exact match including the query string
+            let path_without_query = request.path.split('?').next().unwrap_or_default();
+            let response = match files
+                .iter()
+                .find(|(p, _)| *p == request.path)
+                .or_else(|| files.iter().find(|(p, _)| *p == path_without_query))
+            {
+                Some((_, body)) => format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                ),
+                None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_owned(),
+            };
+            received.lock().unwrap().push(request);
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+    (base_url, requests)
+}
+
+/// Empty directory unique to this test run.
+pub(crate) fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("client-api-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
@@ -1,7 +1,6 @@
 #![allow(clippy::struct_excessive_bools)]
 use crate::Error;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 use std::{collections::HashMap, fmt::Display};
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
@@ -625,6 +624,9 @@ pub struct LolMatchHistoryMatchHistoryParticipantIdentityPlayer {
     pub current_account_id: u64,
     pub match_history_uri: String,
     pub profile_icon: i32,
+    /// Missing from games exported before it was added.
+    #[serde(default)]
+    pub puuid: String,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
@@ -776,7 +778,7 @@ pub struct LolChampSelectLegacyChampSelectSession {
     pub my_team: Vec<LolChampSelectLegacyChampSelectPlayerSelection>,
     pub their_team: Vec<LolChampSelectLegacyChampSelectPlayerSelection>,
     pub trades: Vec<LolChampSelectLegacyChampSelectTradeContract>,
-    pub actions: Vec<Value>,
+    pub actions: Vec<Vec<LolChampSelectLegacyChampSelectAction>>,
     pub bans: LolChampSelectLegacyChampSelectBannedChampions,
     pub local_player_cell_id: i64,
     pub is_spectating: bool,
@@ -860,6 +862,145 @@ pub struct LolChampSelectLegacyChampSelectBannedChampions {
     pub num_bans: i32,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LolChampSelectLegacyChampSelectAction {
+    pub id: Option<i64>,
+    pub actor_cell_id: Option<i64>,
+    pub champion_id: Option<i32>,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub completed: Option<bool>,
+    pub is_ally_action: Option<bool>,
+    pub is_in_progress: Option<bool>,
+}
+
+impl From<LolChampSelectLegacyChampSelectSession> for LolChampSelectChampSelectSession {
+    fn from(value: LolChampSelectLegacyChampSelectSession) -> Self {
+        Self {
+            timer: value.timer.into(),
+            chat_details: value.chat_details.into(),
+            my_team: value.my_team.into_iter().map(Into::into).collect(),
+            their_team: value.their_team.into_iter().map(Into::into).collect(),
+            trades: value.trades.into_iter().map(Into::into).collect(),
+            actions: value
+                .actions
+                .into_iter()
+                .map(|turn| turn.into_iter().map(Into::into).collect())
+                .collect(),
+            bans: value.bans.into(),
+            local_player_cell_id: value.local_player_cell_id,
+            is_spectating: value.is_spectating,
+            allow_skin_selection: value.allow_skin_selection,
+            allow_battle_boost: value.allow_battle_boost,
+            allow_rerolling: value.allow_rerolling,
+            rerolls_remaining: value.rerolls_remaining,
+            has_simultaneous_bans: value.has_simultaneous_bans,
+            has_simultaneous_picks: value.has_simultaneous_picks,
+            is_custom_game: value.is_custom_game,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<LolChampSelectLegacyChampSelectTimer> for LolChampSelectChampSelectTimer {
+    fn from(value: LolChampSelectLegacyChampSelectTimer) -> Self {
+        Self {
+            adjusted_time_left_in_phase: value.adjusted_time_left_in_phase,
+            total_time_in_phase: value.total_time_in_phase,
+            phase: value.phase,
+            is_infinite: value.is_infinite,
+            internal_now_in_epoch_ms: value.internal_now_in_epoch_ms,
+        }
+    }
+}
+
+impl From<LolChampSelectLegacyChampSelectChatRoomDetails>
+    for LolChampSelectChampSelectChatRoomDetails
+{
+    fn from(value: LolChampSelectLegacyChampSelectChatRoomDetails) -> Self {
+        Self {
+            multi_user_chat_id: value.multi_user_chat_id,
+            multi_user_chat_password: value.multi_user_chat_password,
+            muc_jwt_dto: LolChampSelectMucJwtDto {
+                jwt: value.muc_jwt_dto.jwt,
+                channel_claim: value.muc_jwt_dto.channel_claim,
+                domain: value.muc_jwt_dto.domain,
+                target_region: value.muc_jwt_dto.target_region,
+            },
+        }
+    }
+}
+
+impl From<LolChampSelectLegacyChampSelectPlayerSelection>
+    for LolChampSelectChampSelectPlayerSelection
+{
+    fn from(value: LolChampSelectLegacyChampSelectPlayerSelection) -> Self {
+        Self {
+            cell_id: value.cell_id,
+            champion_id: value.champion_id,
+            selected_skin_id: value.selected_skin_id,
+            ward_skin_id: value.ward_skin_id,
+            spell1_id: value.spell1_id,
+            spell2_id: value.spell2_id,
+            team: value.team,
+            assigned_position: value.assigned_position,
+            champion_pick_intent: value.champion_pick_intent,
+            summoner_id: value.summoner_id,
+            puuid: value.puuid,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<LolChampSelectLegacyChampSelectTradeContract> for LolChampSelectChampSelectTradeContract {
+    fn from(value: LolChampSelectLegacyChampSelectTradeContract) -> Self {
+        Self {
+            id: value.id,
+            cell_id: value.cell_id,
+            state: value.state.into(),
+        }
+    }
+}
+
+impl From<LolChampSelectLegacyChampSelectTradeState> for LolChampSelectChampSelectTradeState {
+    fn from(value: LolChampSelectLegacyChampSelectTradeState) -> Self {
+        match value {
+            LolChampSelectLegacyChampSelectTradeState::Available => Self::Available,
+            LolChampSelectLegacyChampSelectTradeState::Busy => Self::Busy,
+            LolChampSelectLegacyChampSelectTradeState::Invalid => Self::Invalid,
+            LolChampSelectLegacyChampSelectTradeState::Received => Self::Received,
+            LolChampSelectLegacyChampSelectTradeState::Sent => Self::Sent,
+        }
+    }
+}
+
+impl From<LolChampSelectLegacyChampSelectBannedChampions>
+    for LolChampSelectChampSelectBannedChampions
+{
+    fn from(value: LolChampSelectLegacyChampSelectBannedChampions) -> Self {
+        Self {
+            my_team_bans: value.my_team_bans,
+            their_team_bans: value.their_team_bans,
+            num_bans: value.num_bans,
+        }
+    }
+}
+
+impl From<LolChampSelectLegacyChampSelectAction> for LolChampSelectChampSelectAction {
+    fn from(value: LolChampSelectLegacyChampSelectAction) -> Self {
+        Self {
+            id: value.id,
+            actor_cell_id: value.actor_cell_id,
+            champion_id: value.champion_id,
+            type_: value.type_,
+            completed: value.completed,
+            is_ally_action: value.is_ally_action,
+            is_in_progress: value.is_in_progress,
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct LolChampSelectChampSelectAction {
@@ -892,6 +1033,19 @@ pub struct LolChampSelectChampSelectPlayerSelection {
     pub obfuscated_puuid: String,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LolChampSelectChampSelectMySelection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_skin_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spell1_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spell2_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ward_skin_id: Option<i64>,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct LolChampSelectChampSelectSession {
@@ -1009,3 +1163,505 @@ pub struct LolChampSelectBenchChampion {
     pub champion_id: i32,
     pub is_priority: bool,
 }
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LolGameflowGameflowPhase {
+    #[default]
+    None,
+    Lobby,
+    Matchmaking,
+    CheckedIntoTournament,
+    ReadyCheck,
+    ChampSelect,
+    GameStart,
+    FailedToLaunch,
+    InProgress,
+    Reconnect,
+    WaitingForStats,
+    PreEndOfGame,
+    EndOfGame,
+    TerminatedInError,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LolGameflowGameflowSession {
+    pub phase: LolGameflowGameflowPhase,
+    pub game_data: LolGameflowGameflowGameData,
+    pub map: LolGameflowGameflowGameMap,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LolGameflowGameflowGameData {
+    pub game_id: u64,
+    pub is_custom_game: bool,
+    pub queue: LolGameflowQueue,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LolGameflowQueue {
+    pub id: i32,
+    pub map_id: i32,
+    pub game_mode: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LolGameflowGameflowGameMap {
+    pub id: i32,
+    pub game_mode: String,
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LolEndOfGameEndOfGameStats {
+    pub game_id: u64,
+    pub game_length: u32,
+    pub game_mode: String,
+    pub game_type: String,
+    pub queue_type: String,
+    pub teams: Vec<LolEndOfGameEndOfGameTeam>,
+    pub local_player: Option<LolEndOfGameEndOfGamePlayer>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LolEndOfGameEndOfGameTeam {
+    pub team_id: u32,
+    pub is_winning_team: bool,
+    pub is_player_team: bool,
+    pub players: Vec<LolEndOfGameEndOfGamePlayer>,
+    pub stats: HashMap<String, i64>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LolEndOfGameEndOfGamePlayer {
+    pub champion_id: i32,
+    pub puuid: String,
+    pub summoner_id: u64,
+    pub summoner_name: String,
+    pub team_id: u32,
+    pub bot_player: bool,
+    pub profile_icon_id: i32,
+    pub spell1_id: u32,
+    pub spell2_id: u32,
+    pub items: Vec<u32>,
+    pub stats: HashMap<String, i64>,
+}
+
+/// Converts the stats block into the match history shape. The stats block has no
+/// map or creation time, so `map_id` and `game_creation` are left at 0.
+impl From<LolEndOfGameEndOfGameStats> for LolMatchHistoryMatchHistoryGame {
+    fn from(value: LolEndOfGameEndOfGameStats) -> Self {
+        let players = value
+            .teams
+            .iter()
+            .flat_map(|team| team.players.iter().map(move |player| (team, player)));
+
+        Self {
+            game_id: value.game_id,
+            game_duration: value.game_length,
+            participants: players
+                .clone()
+                .zip(1..)
+                .map(
+                    |((team, player), participant_id)| LolMatchHistoryMatchHistoryParticipant {
+                        participant_id,
+                        team_id: team.team_id,
+                        champion_id: player.champion_id,
+                        spell1_id: player.spell1_id,
+                        spell2_id: player.spell2_id,
+                        stats: LolMatchHistoryMatchHistoryParticipantStatistics::from_end_of_game(
+                            participant_id,
+                            team.is_winning_team,
+                            player,
+                        ),
+                        timeline: LolMatchHistoryMatchHistoryTimeline {
+                            participant_id,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                )
+                .collect(),
+            participant_identities: players
+                .zip(1..)
+                .map(|((_, player), participant_id)| {
+                    LolMatchHistoryMatchHistoryParticipantIdentities {
+                        participant_id,
+                        player: LolMatchHistoryMatchHistoryParticipantIdentityPlayer {
+                            summoner_id: player.summoner_id,
+                            summoner_name: player.summoner_name.clone(),
+                            profile_icon: player.profile_icon_id,
+                            puuid: player.puuid.clone(),
+                            ..Default::default()
+                        },
+                    }
+                })
+                .collect(),
+            teams: value
+                .teams
+                .iter()
+                .map(|team| LolMatchHistoryMatchHistoryTeam {
+                    team_id: team.team_id,
+                    win: if team.is_winning_team { "Win" } else { "Fail" }.to_owned(),
+                    tower_kills: team
+                        .players
+                        .iter()
+                        .map(|x| x.stats.get("TURRETS_KILLED").copied().unwrap_or_default())
+                        .sum::<i64>()
+                        .try_into()
+                        .unwrap_or_default(),
+                    ..Default::default()
+                })
+                .collect(),
+            game_mode: value.game_mode,
+            game_type: value.game_type,
+            ..Default::default()
+        }
+    }
+}
+
+impl LolMatchHistoryMatchHistoryParticipantStatistics {
+    fn from_end_of_game(
+        participant_id: u32,
+        win: bool,
+        player: &LolEndOfGameEndOfGamePlayer,
+    ) -> Self {
+        let stat = |key: &str| player.stats.get(key).copied().unwrap_or_default();
+        let item = |slot: usize| player.items.get(slot).copied().unwrap_or_default();
+
+        Self {
+            participant_id,
+            win,
+            item0: item(0),
+            item1: item(1),
+            item2: item(2),
+            item3: item(3),
+            item4: item(4),
+            item5: item(5),
+            item6: item(6),
+            kills: stat("CHAMPIONS_KILLED"),
+            deaths: stat("NUM_DEATHS"),
+            assists: stat("ASSISTS"),
+            largest_killing_spree: stat("LARGEST_KILLING_SPREE"),
+            largest_multi_kill: stat("LARGEST_MULTI_KILL"),
+            killing_sprees: stat("KILLING_SPREES"),
+            longest_time_spent_living: stat("LONGEST_TIME_SPENT_LIVING"),
+            double_kills: stat("DOUBLE_KILLS"),
+            triple_kills: stat("TRIPLE_KILLS"),
+            quadra_kills: stat("QUADRA_KILLS"),
+            penta_kills: stat("PENTA_KILLS"),
+            unreal_kills: stat("UNREAL_KILLS"),
+            total_damage_dealt: stat("TOTAL_DAMAGE_DEALT"),
+            magic_damage_dealt: stat("MAGIC_DAMAGE_DEALT_PLAYER"),
+            physical_damage_dealt: stat("PHYSICAL_DAMAGE_DEALT_PLAYER"),
+            true_damage_dealt: stat("TRUE_DAMAGE_DEALT_PLAYER"),
+            largest_critical_strike: stat("LARGEST_CRITICAL_STRIKE"),
+            total_damage_dealt_to_champions: stat("TOTAL_DAMAGE_DEALT_TO_CHAMPIONS"),
+            magic_damage_dealt_to_champions: stat("MAGIC_DAMAGE_DEALT_TO_CHAMPIONS"),
+            physical_damage_dealt_to_champions: stat("PHYSICAL_DAMAGE_DEALT_TO_CHAMPIONS"),
+            true_damage_dealt_to_champions: stat("TRUE_DAMAGE_DEALT_TO_CHAMPIONS"),
+            total_heal: stat("TOTAL_HEAL"),
+            total_units_healed: stat("TOTAL_UNITS_HEALED"),
+            total_damage_taken: stat("TOTAL_DAMAGE_TAKEN"),
+            magical_damage_taken: stat("MAGIC_DAMAGE_TAKEN"),
+            physical_damage_taken: stat("PHYSICAL_DAMAGE_TAKEN"),
+            true_damage_taken: stat("TRUE_DAMAGE_TAKEN"),
+            gold_earned: stat("GOLD_EARNED"),
+            gold_spent: stat("GOLD_SPENT"),
+            turret_kills: stat("TURRETS_KILLED"),
+            inhibitor_kills: stat("BARRACKS_KILLED"),
+            total_minions_killed: stat("MINIONS_KILLED"),
+            neutral_minions_killed: stat("NEUTRAL_MINIONS_KILLED"),
+            neutral_minions_killed_team_jungle: stat("NEUTRAL_MINIONS_KILLED_YOUR_JUNGLE"),
+            neutral_minions_killed_enemy_jungle: stat("NEUTRAL_MINIONS_KILLED_ENEMY_JUNGLE"),
+            total_time_crowd_control_dealt: stat("TOTAL_TIME_CROWD_CONTROL_DEALT"),
+            champ_level: stat("LEVEL"),
+            vision_wards_bought_in_game: stat("VISION_WARDS_BOUGHT_IN_GAME"),
+            sight_wards_bought_in_game: stat("SIGHT_WARDS_BOUGHT_IN_GAME"),
+            wards_placed: stat("WARD_PLACED"),
+            wards_killed: stat("WARD_KILLED"),
+            damage_self_mitigated: stat("TOTAL_DAMAGE_SELF_MITIGATED"),
+            damage_dealt_to_objectives: stat("TOTAL_DAMAGE_DEALT_TO_OBJECTIVES"),
+            damage_dealt_to_turrets: stat("TOTAL_DAMAGE_DEALT_TO_BUILDINGS"),
+            vision_score: stat("VISION_SCORE"),
+            time_c_cing_others: stat("TIME_CCING_OTHERS"),
+            perk_primary_style: stat("PERK_PRIMARY_STYLE"),
+            perk_sub_style: stat("PERK_SUB_STYLE"),
+            perk0: stat("PERK0"),
+            perk0_var1: stat("PERK0_VAR1"),
+            perk0_var2: stat("PERK0_VAR2"),
+            perk0_var3: stat("PERK0_VAR3"),
+            perk1: stat("PERK1"),
+            perk1_var1: stat("PERK1_VAR1"),
+            perk1_var2: stat("PERK1_VAR2"),
+            perk1_var3: stat("PERK1_VAR3"),
+            perk2: stat("PERK2"),
+            perk2_var1: stat("PERK2_VAR1"),
+            perk2_var2: stat("PERK2_VAR2"),
+            perk2_var3: stat("PERK2_VAR3"),
+            perk3: stat("PERK3"),
+            perk3_var1: stat("PERK3_VAR1"),
+            perk3_var2: stat("PERK3_VAR2"),
+            perk3_var3: stat("PERK3_VAR3"),
+            perk4: stat("PERK4"),
+            perk4_var1: stat("PERK4_VAR1"),
+            perk4_var2: stat("PERK4_VAR2"),
+            perk4_var3: stat("PERK4_VAR3"),
+            perk5: stat("PERK5"),
+            perk5_var1: stat("PERK5_VAR1"),
+            perk5_var2: stat("PERK5_VAR2"),
+            perk5_var3: stat("PERK5_VAR3"),
+            ..Default::default()
+        }
+    }
+}
+
+/// Item id as used in match history, `0` for an empty slot.
+#[derive(
+    Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+#[serde(transparent)]
+pub struct ItemId(pub u32);
+
+impl ItemId {
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl Display for ItemId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A rune picked by a participant, with the values shown in its end of game tooltip,
+/// e.g. the damage it dealt.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PerkSelection {
+    pub perk: i64,
+    pub vars: [i64; 3],
+}
+
+/// Kills and assists per death, counting no deaths as one death.
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn kda_ratio(kills: i64, deaths: i64, assists: i64) -> f64 {
+    (kills + assists) as f64 / deaths.max(1) as f64
+}
+
+impl LolMatchHistoryMatchHistoryParticipantStatistics {
+    /// Item slots 0 to 5 followed by the trinket. Empty slots are included.
+    #[must_use]
+    pub const fn items(&self) -> [ItemId; 7] {
+        [
+            ItemId(self.item0),
+            ItemId(self.item1),
+            ItemId(self.item2),
+            ItemId(self.item3),
+            ItemId(self.item4),
+            ItemId(self.item5),
+            ItemId(self.item6),
+        ]
+    }
+
+    /// Selected runes in order, keystone first. Empty slots are left out.
+    #[must_use]
+    pub fn perks(&self) -> Vec<PerkSelection> {
+        [
+            (
+                self.perk0,
+                [self.perk0_var1, self.perk0_var2, self.perk0_var3],
+            ),
+            (
+                self.perk1,
+                [self.perk1_var1, self.perk1_var2, self.perk1_var3],
+            ),
+            (
+                self.perk2,
+                [self.perk2_var1, self.perk2_var2, self.perk2_var3],
+            ),
+            (
+                self.perk3,
+                [self.perk3_var1, self.perk3_var2, self.perk3_var3],
+            ),
+            (
+                self.perk4,
+                [self.perk4_var1, self.perk4_var2, self.perk4_var3],
+            ),
+            (
+                self.perk5,
+                [self.perk5_var1, self.perk5_var2, self.perk5_var3],
+            ),
+        ]
+        .into_iter()
+        .filter(|&(perk, _)| perk != 0)
+        .map(|(perk, vars)| PerkSelection { perk, vars })
+        .collect()
+    }
+
+    #[must_use]
+    pub const fn player_scores(&self) -> [i64; 10] {
+        [
+            self.player_score0,
+            self.player_score1,
+            self.player_score2,
+            self.player_score3,
+            self.player_score4,
+            self.player_score5,
+            self.player_score6,
+            self.player_score7,
+            self.player_score8,
+            self.player_score9,
+        ]
+    }
+
+    /// Kills and assists per death, counting deathless games as one death.
+    #[must_use]
+    pub fn kda(&self) -> f64 {
+        kda_ratio(self.kills, self.deaths, self.assists)
+    }
+
+    /// Lane minions and jungle monsters killed.
+    #[must_use]
+    pub const fn cs(&self) -> i64 {
+        self.total_minions_killed + self.neutral_minions_killed
+    }
+
+    /// Share of `team_kills` the player got a kill or assist in.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn kill_participation(&self, team_kills: i64) -> f64 {
+        if team_kills == 0 {
+            0.0
+        } else {
+            (self.kills + self.assists) as f64 / team_kills as f64
+        }
+    }
+}
+
+impl LolMatchHistoryMatchHistoryParticipant {
+    /// Share of the team's kills in `game` the participant got a kill or assist in.
+    #[must_use]
+    pub fn kill_participation(&self, game: &LolMatchHistoryMatchHistoryGame) -> f64 {
+        self.stats.kill_participation(game.team_kills(self.team_id))
+    }
+}
+
+impl LolMatchHistoryMatchHistoryGame {
+    #[must_use]
+    pub fn team_kills(&self, team_id: u32) -> i64 {
+        self.participants
+            .iter()
+            .filter(|x| x.team_id == team_id)
+            .map(|x| x.stats.kills)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ItemId, LolChampSelectChampSelectSession, LolChampSelectChampSelectTradeState,
+        LolChampSelectLegacyChampSelectSession, LolMatchHistoryMatchHistoryParticipantStatistics,
+        PerkSelection,
+    };
+
+    #[test]
+    fn participant_accessors() {
+        let stats = LolMatchHistoryMatchHistoryParticipantStatistics {
+            item0: 3089,
+            item6: 3340,
+            perk0: 8112,
+            perk0_var1: 1200,
+            perk2: 8138,
+            perk2_var3: 3,
+            player_score9: 7,
+            kills: 4,
+            deaths: 0,
+            assists: 2,
+            total_minions_killed: 150,
+            neutral_minions_killed: 12,
+            ..Default::default()
+        };
+
+        let items = stats.items();
+        assert_eq!(items[0], ItemId(3089));
+        assert!(items[1].is_empty());
+        assert_eq!(items[6].to_string(), "3340");
+        assert_eq!(
+            stats.perks(),
+            [
+                PerkSelection {
+                    perk: 8112,
+                    vars: [1200, 0, 0]
+                },
+                PerkSelection {
+                    perk: 8138,
+                    vars: [0, 0, 3]
+                },
+            ]
+        );
+        assert_eq!(stats.player_scores()[9], 7);
+        assert_eq!(stats.kda(), 6.0);
+        assert_eq!(stats.cs(), 162);
+        assert_eq!(stats.kill_participation(12), 0.5);
+        assert_eq!(stats.kill_participation(0), 0.0);
+    }
+
+    /// A session in the shape older clients sent, converted to the modern one
+    #[test]
+    fn legacy_champ_select_session() -> Result<(), serde_json::Error> {
+        let legacy: LolChampSelectLegacyChampSelectSession =
+            serde_json::from_str(include_str!("../fixtures/champ-select/legacy-session.json"))?;
+        assert_eq!(legacy.actions[0][1].type_.as_deref(), Some("ban"));
+        assert_eq!(legacy.actions[0][1].actor_cell_id, Some(5));
+
+        let session: LolChampSelectChampSelectSession = legacy.into();
+        // The converted session survives its own serialization
+        let session: LolChampSelectChampSelectSession =
+            serde_json::from_value(serde_json::to_value(session)?)?;
+
+        assert_eq!(session.timer.phase, "BAN_PICK");
+        assert_eq!(session.timer.internal_now_in_epoch_ms, 1_591_204_861_420);
+        assert_eq!(session.chat_details.muc_jwt_dto.target_region, "euw1");
+        assert_eq!(session.local_player_cell_id, 1);
+        assert!(session.allow_skin_selection);
+
+        assert_eq!(session.my_team.len(), 2);
+        assert_eq!(session.my_team[0].champion_id, 103);
+        assert_eq!(session.my_team[0].ward_skin_id, -1);
+        assert_eq!(session.my_team[1].champion_pick_intent, 64);
+        assert_eq!(session.their_team[0].cell_id, 5);
+
+        let shape: Vec<_> = session.actions.iter().map(Vec::len).collect();
+        assert_eq!(shape, [2, 1, 1]);
+        let current = &session.actions[2][0];
+        assert_eq!(current.type_.as_deref(), Some("pick"));
+        assert_eq!(current.actor_cell_id, Some(1));
+        assert_eq!(current.is_in_progress, Some(true));
+        assert_eq!(current.completed, Some(false));
+
+        assert_eq!(session.bans.my_team_bans, [55]);
+        assert_eq!(session.bans.their_team_bans, [157]);
+        assert_eq!(session.bans.num_bans, 6);
+
+        assert!(matches!(
+            session.trades[0].state,
+            LolChampSelectChampSelectTradeState::Available
+        ));
+        assert!(matches!(
+            session.trades[1].state,
+            LolChampSelectChampSelectTradeState::Invalid
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use std::{env, net::SocketAddr, path::PathBuf};
+
+use eyre::Result;
+use routes::AppState;
+
+mod routes;
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+const DEFAULT_DATA: &str = "matches.jsonl";
+
+/// Serves the custom game stats api used by `lol-custom-match-history-ui`.
+///
+/// Configured through the environment:
+/// - `STATS_SERVER_ADDR`: address to listen on, defaults to `127.0.0.1:8080`
+/// - `STATS_SERVER_DATA`: json lines file games are stored in, defaults to `matches.jsonl`
+/// - `STATS_SERVER_TOKEN`: bearer token required to post games, unset allows anyone
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::init();
+
+    let addr: SocketAddr = env::var("STATS_SERVER_ADDR")
+        .unwrap_or_else(|_| DEFAULT_ADDR.to_owned())
+        .parse()?;
+    let data =
+        PathBuf::from(env::var("STATS_SERVER_DATA").unwrap_or_else(|_| DEFAULT_DATA.to_owned()));
+    let token = env::var("STATS_SERVER_TOKEN").ok();
+
+    let state = AppState::open(&data, token)?;
+    log::info!("Loaded {} games from {}", state.len().await, data.display());
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    log::info!("Listening on {addr}");
+    axum::serve(listener, routes::router(state)).await?;
+    Ok(())
+}
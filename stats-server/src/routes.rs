@@ -0,0 +1,442 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::{Path as UrlPath, Query, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use client_api::{
+    stats::{
+        awards::{AwardsConfig, GameAwards},
+        lanes::{ChampionMatchup, PlayerLaneLeads},
+        pairs::PairStats,
+        ChampionStats, Kda, MatchStore, Order, PerMinuteStat, StatPerMin, SummonerChampion,
+        SummonerOrder, SummonerSummary,
+    },
+    types::LolMatchHistoryMatchHistoryGame,
+};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+
+/// Games served by the api, backed by a json lines file that new games are appended to.
+#[derive(Debug, Clone)]
+pub struct AppState(Arc<Inner>);
+
+#[derive(Debug)]
+struct Inner {
+    store: RwLock<MatchStore>,
+    file: Mutex<File>,
+    token: Option<String>,
+}
+
+impl AppState {
+    /// Loads the games stored at `path`, creating the file if it does not exist.
+    pub fn open(path: &Path, token: Option<String>) -> eyre::Result<Self> {
+        let store = if path.exists() {
+            MatchStore::load_json_lines(path)?
+        } else {
+            MatchStore::new()
+        };
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self(Arc::new(Inner {
+            store: RwLock::new(store),
+            file: Mutex::new(file),
+            token,
+        })))
+    }
+
+    pub async fn len(&self) -> usize {
+        self.0.store.read().await.len()
+    }
+
+    async fn insert(&self, game: LolMatchHistoryMatchHistoryGame) -> eyre::Result<bool> {
+        let mut store = self.0.store.write().await;
+        {
+            let mut file = self.0.file.lock().unwrap_or_else(|e| e.into_inner());
+            let mut line = serde_json::to_vec(&game)?;
+            line.push(b'\n');
+            file.write_all(&line)?;
+        }
+        Ok(store.insert(game))
+    }
+
+    fn is_authorized(&self, headers: &HeaderMap) -> bool {
+        let Some(token) = &self.0.token else {
+            return true;
+        };
+        headers
+            .get(AUTHORIZATION)
+            .and_then(|x| x.to_str().ok())
+            .and_then(|x| x.strip_prefix("Bearer "))
+            .is_some_and(|x| x == token)
+    }
+}
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/match", post(post_match))
+        .route("/summoner_names", get(summoner_names))
+        .route("/summoner/:id", get(summoner))
+        .route("/summoner_matches/:id", get(summoner_matches))
+        .route("/summoner_stats/:id", get(summoner_stats))
+        .route("/kdas", get(kdas))
+        .route("/stat_per_min/:stat", get(stat_per_min))
+        .route("/lossless_champions", get(lossless_champions))
+        .route("/duos", get(duos))
+        .route("/summoner_pairs/:id", get(summoner_pairs))
+        .route("/head_to_head/:id/:other_id", get(head_to_head))
+        .route("/lane_leads", get(lane_leads))
+        .route("/champion_matchups", get(champion_matchups))
+        .route("/awards/:game_id", get(awards))
+        .layer(tower_http::cors::CorsLayer::permissive())
+        .with_state(state)
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct SummonerNamesQuery {
+    order_by: SummonerOrder,
+    cutoff: u32,
+    order: Order,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+struct CutoffQuery {
+    cutoff: u32,
+    order: Order,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct PageQuery {
+    amount: usize,
+    offset: usize,
+}
+
+impl Default for PageQuery {
+    fn default() -> Self {
+        Self {
+            amount: 20,
+            offset: 0,
+        }
+    }
+}
+
+async fn post_match(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(game): Json<LolMatchHistoryMatchHistoryGame>,
+) -> StatusCode {
+    if !state.is_authorized(&headers) {
+        return StatusCode::UNAUTHORIZED;
+    }
+    if game.participants.is_empty() {
+        return StatusCode::UNPROCESSABLE_ENTITY;
+    }
+
+    let game_id = game.game_id;
+    match state.insert(game).await {
+        Ok(true) => {
+            log::info!("Stored game {game_id}");
+            StatusCode::CREATED
+        }
+        Ok(false) => {
+            log::info!("Replaced game {game_id}");
+            StatusCode::OK
+        }
+        Err(e) => {
+            log::error!("Failed to store game {game_id}: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+async fn summoner_names(
+    State(state): State<AppState>,
+    Query(query): Query<SummonerNamesQuery>,
+) -> Json<Vec<SummonerSummary>> {
+    let store = state.0.store.read().await;
+    Json(store.summoner_names(query.order_by, query.cutoff, query.order))
+}
+
+async fn summoner(
+    State(state): State<AppState>,
+    UrlPath(summoner_id): UrlPath<u64>,
+) -> Result<Json<SummonerSummary>, StatusCode> {
+    let store = state.0.store.read().await;
+    store
+        .summoner(summoner_id)
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn summoner_matches(
+    State(state): State<AppState>,
+    UrlPath(summoner_id): UrlPath<u64>,
+    Query(query): Query<PageQuery>,
+) -> Json<Vec<LolMatchHistoryMatchHistoryGame>> {
+    let store = state.0.store.read().await;
+    Json(
+        store
+            .summoner_matches(summoner_id, query.amount, query.offset)
+            .into_iter()
+            .cloned()
+            .collect(),
+    )
+}
+
+async fn summoner_stats(
+    State(state): State<AppState>,
+    UrlPath(summoner_id): UrlPath<u64>,
+) -> Json<Vec<ChampionStats>> {
+    let store = state.0.store.read().await;
+    Json(store.summoner_stats(summoner_id))
+}
+
+async fn kdas(State(state): State<AppState>, Query(query): Query<CutoffQuery>) -> Json<Vec<Kda>> {
+    let store = state.0.store.read().await;
+    Json(store.kdas(query.cutoff, query.order))
+}
+
+async fn stat_per_min(
+    State(state): State<AppState>,
+    UrlPath(stat): UrlPath<PerMinuteStat>,
+    Query(query): Query<CutoffQuery>,
+) -> Json<Vec<StatPerMin>> {
+    let store = state.0.store.read().await;
+    Json(store.stat_per_min(stat, query.cutoff, query.order))
+}
+
+async fn lossless_champions(
+    State(state): State<AppState>,
+    Query(query): Query<CutoffQuery>,
+) -> Json<Vec<SummonerChampion>> {
+    let store = state.0.store.read().await;
+    Json(store.lossless_champions(query.cutoff, query.order))
+}
+
+async fn duos(
+    State(state): State<AppState>,
+    Query(query): Query<CutoffQuery>,
+) -> Json<Vec<PairStats>> {
+    let store = state.0.store.read().await;
+    Json(store.duos(query.cutoff, query.order))
+}
+
+async fn summoner_pairs(
+    State(state): State<AppState>,
+    UrlPath(summoner_id): UrlPath<u64>,
+) -> Json<Vec<PairStats>> {
+    let store = state.0.store.read().await;
+    Json(store.summoner_pairs(summoner_id))
+}
+
+async fn head_to_head(
+    State(state): State<AppState>,
+    UrlPath((summoner_id, other_summoner_id)): UrlPath<(u64, u64)>,
+) -> Result<Json<PairStats>, StatusCode> {
+    let store = state.0.store.read().await;
+    store
+        .head_to_head(summoner_id, other_summoner_id)
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn lane_leads(
+    State(state): State<AppState>,
+    Query(query): Query<CutoffQuery>,
+) -> Json<Vec<PlayerLaneLeads>> {
+    let store = state.0.store.read().await;
+    Json(store.player_lane_leads(query.cutoff, query.order))
+}
+
+async fn champion_matchups(
+    State(state): State<AppState>,
+    Query(query): Query<CutoffQuery>,
+) -> Json<Vec<ChampionMatchup>> {
+    let store = state.0.store.read().await;
+    Json(store.champion_matchups(query.cutoff, query.order))
+}
+
+async fn awards(
+    State(state): State<AppState>,
+    UrlPath(game_id): UrlPath<u64>,
+) -> Result<Json<GameAwards>, StatusCode> {
+    let store = state.0.store.read().await;
+    store
+        .get(game_id)
+        .map(|game| Json(AwardsConfig::default().evaluate(game)))
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{router, AppState};
+    use axum::{
+        body::{to_bytes, Body},
+        http::{header::AUTHORIZATION, Request, StatusCode},
+        Router,
+    };
+    use client_api::types::{
+        LolMatchHistoryMatchHistoryGame, LolMatchHistoryMatchHistoryParticipant,
+        LolMatchHistoryMatchHistoryParticipantIdentities,
+        LolMatchHistoryMatchHistoryParticipantIdentityPlayer,
+        LolMatchHistoryMatchHistoryParticipantStatistics,
+    };
+    use serde_json::Value;
+    use std::{env, fs, path::PathBuf};
+    use tower::ServiceExt;
+
+    fn data_file(name: &str) -> PathBuf {
+        let path =
+            env::temp_dir().join(format!("stats-server-{name}-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    /// Summoner 1 on champion 1 beats summoner 2 on champion 2 in a 10 minute game.
+    fn game(game_id: u64) -> LolMatchHistoryMatchHistoryGame {
+        LolMatchHistoryMatchHistoryGame {
+            game_id,
+            game_creation: game_id,
+            game_duration: 600,
+            participants: (1..=2)
+                .map(|i| LolMatchHistoryMatchHistoryParticipant {
+                    participant_id: i,
+                    team_id: i * 100,
+                    champion_id: i32::try_from(i).unwrap(),
+                    stats: LolMatchHistoryMatchHistoryParticipantStatistics {
+                        win: i == 1,
+                        kills: 3,
+                        deaths: i64::from(i),
+                        total_minions_killed: 60,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .collect(),
+            participant_identities: (1..=2)
+                .map(|i| LolMatchHistoryMatchHistoryParticipantIdentities {
+                    participant_id: i,
+                    player: LolMatchHistoryMatchHistoryParticipantIdentityPlayer {
+                        summoner_id: u64::from(i),
+                        summoner_name: format!("Summoner {i}"),
+                        ..Default::default()
+                    },
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    async fn post(app: &Router, game_id: u64, token: Option<&str>) -> StatusCode {
+        let mut request = Request::post("/match").header("content-type", "application/json");
+        if let Some(token) = token {
+            request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        let body = Body::from(serde_json::to_vec(&game(game_id)).unwrap());
+        app.clone()
+            .oneshot(request.body(body).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    async fn get(app: &Router, uri: &str) -> Value {
+        let response = app
+            .clone()
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK, "{uri}");
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn serves_ui_routes() {
+        let path = data_file("routes");
+        let app = router(AppState::open(&path, None).unwrap());
+        assert_eq!(post(&app, 1, None).await, StatusCode::CREATED);
+        assert_eq!(post(&app, 2, None).await, StatusCode::CREATED);
+        assert_eq!(post(&app, 2, None).await, StatusCode::OK);
+
+        let names = get(
+            &app,
+            "/summoner_names?order_by=winrate&cutoff=2&order=ascending",
+        )
+        .await;
+        assert_eq!(names[0]["summonerName"], "Summoner 2");
+        assert_eq!(names[0]["winrate"], 0.0);
+        assert_eq!(names[1]["games"], 2);
+        assert_eq!(
+            get(&app, "/summoner_names").await.as_array().unwrap().len(),
+            2
+        );
+
+        assert_eq!(get(&app, "/summoner/1").await["champs"], 1);
+        let matches = get(&app, "/summoner_matches/1?amount=1&offset=1").await;
+        assert_eq!(matches[0]["gameId"], 1);
+        let stats = get(&app, "/summoner_stats/2").await;
+        assert_eq!(stats[0]["losses"], 2);
+
+        let kdas = get(&app, "/kdas?cutoff=10&order=descending").await;
+        assert_eq!(kdas.as_array().unwrap().len(), 0);
+        let kdas = get(&app, "/kdas?order=descending").await;
+        assert_eq!(kdas[0]["kda"], 3.0);
+
+        let cs = get(
+            &app,
+            "/stat_per_min/minions_killed?cutoff=1&order=descending",
+        )
+        .await;
+        assert_eq!(cs[0]["stat"], 6.0);
+        let lossless = get(&app, "/lossless_champions?cutoff=2").await;
+        assert_eq!(lossless[0]["championId"], 1);
+        assert_eq!(lossless[0]["wins"], 2);
+        assert_eq!(
+            get(&app, "/lossless_champions?cutoff=2&order=ascending").await,
+            lossless
+        );
+
+        let duos = get(&app, "/duos").await;
+        assert_eq!(duos.as_array().unwrap().len(), 0);
+        assert_eq!(
+            get(&app, "/summoner_pairs/2").await[0]["otherSummonerId"],
+            1
+        );
+        let head_to_head = get(&app, "/head_to_head/1/2").await;
+        assert_eq!(head_to_head["gamesAgainst"], 2);
+        assert_eq!(head_to_head["winsAgainst"], 2);
+        let awards = get(&app, "/awards/1").await;
+        assert_eq!(awards["awards"].as_array().unwrap().len(), 5);
+
+        // Games survive a restart
+        let app = router(AppState::open(&path, None).unwrap());
+        assert_eq!(get(&app, "/summoner/2").await["games"], 2);
+        let _ = fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn rejects_unauthorized_uploads() {
+        let path = data_file("auth");
+        let app = router(AppState::open(&path, Some("secret".to_owned())).unwrap());
+        assert_eq!(post(&app, 1, None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(post(&app, 1, Some("wrong")).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(post(&app, 1, Some("secret")).await, StatusCode::CREATED);
+
+        let response = app
+            .oneshot(Request::get("/summoner/3").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let _ = fs::remove_file(path);
+    }
+}